@@ -0,0 +1,34 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{error::Error, net::TcpStream};
+
+use talk_loco_client::{
+    client::booking::BlockingBookingClient, command::session::BsonCommandSession, request,
+};
+use tokio_native_tls::native_tls;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let connector = native_tls::TlsConnector::new()?;
+
+    let stream = connector.connect(
+        "booking-loco.kakao.com",
+        TcpStream::connect("booking-loco.kakao.com:443")?,
+    )?;
+
+    let mut booking_conn = BsonCommandSession::new(stream);
+    let mut booking_client = BlockingBookingClient(&mut booking_conn);
+
+    let booking_res = booking_client.get_conf(&request::booking::GetConfReq {
+        os: "win32".into(),
+        mccmnc: "999".into(),
+        model: "".into(),
+    })?;
+
+    println!("GETCONF response: {:?}", booking_res);
+
+    Ok(())
+}
@@ -11,7 +11,6 @@ use talk_loco_client::{
 };
 use tokio::{net::TcpStream, io::BufStream};
 use tokio_native_tls::native_tls;
-use tokio_util::compat::TokioAsyncReadCompatExt;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -27,10 +26,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
             ),
         )
         .await
-        .unwrap()
-        .compat();
+        .unwrap();
 
-    let mut booking_conn = BsonCommandSession::new(stream);
+    let mut booking_conn = BsonCommandSession::new_tokio(stream);
     let mut booking_client = BookingClient(&mut booking_conn);
 
     let booking_res = booking_client
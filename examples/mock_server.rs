@@ -0,0 +1,75 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::error::Error;
+
+use futures::StreamExt;
+
+use talk_loco_client::{
+    client::talk::TalkClient,
+    command::{driver::SessionDriver, tokio::TokioStream, BsonCommand},
+    request::chat::WriteReq,
+    response::{
+        chat::{Msg, WriteRes},
+        ResponseData,
+    },
+    server::{LocoServer, Router},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let router = Router::new().route("WRITE", |req: WriteReq| ResponseData {
+        status: 0,
+        data: Some(WriteRes {
+            chat_id: req.chat_id,
+            prev_id: 0,
+            log_id: 1,
+            send_at: 0,
+            msg_id: req.msg_id,
+            chatlog: None,
+        }),
+    });
+
+    let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+    let (connection, pusher) = LocoServer::new(router).accept(TokioStream::new(server_stream));
+    tokio::spawn(connection.run());
+
+    let (driver, mut handle, mut broadcasts) = SessionDriver::new_tokio(client_stream);
+    tokio::spawn(driver.run());
+
+    let write_res = TalkClient(&mut handle)
+        .write(&WriteReq {
+            chat_id: 1,
+            chat_type: 1,
+            msg_id: 1,
+            message: "Hello".into(),
+            no_seen: false,
+            attachment: None,
+            supplement: None,
+        })
+        .await?;
+
+    println!("WRITE response: {:?}", write_res);
+
+    pusher.push(&BsonCommand::new_const(
+        "MSG",
+        0,
+        Msg {
+            chat_id: 1,
+            log_id: 2,
+            chatlog: None,
+            author_nick: None,
+            no_seen: false,
+            link_id: None,
+            noti_read: None,
+        },
+    ))?;
+
+    println!("Pushed: {:?}", broadcasts.next().await);
+
+    Ok(())
+}
@@ -4,12 +4,10 @@
  * Copyright (c) storycraft. Licensed under the MIT Licence.
  */
 
-use std::{borrow::Cow, env, error::Error, thread, time::Duration};
+use std::{borrow::Cow, env, error::Error};
 
-use futures::{AsyncRead, AsyncWrite};
-use loco_protocol::secure::{
-    crypto::CryptoStore, session::SecureClientSession, stream::SecureStream,
-};
+use futures::{pin_mut, StreamExt};
+use loco_protocol::secure::session::SecureClientSession;
 use rsa::{pkcs8::FromPublicKey, RsaPublicKey};
 use talk_api_client::{
     agent::TalkApiAgent,
@@ -21,18 +19,10 @@ use talk_api_client::{
     ApiRequestError,
 };
 use talk_loco_client::{
-    client::{checkin::CheckinClient, talk::TalkClient, RequestResult},
-    command::{session::BsonCommandSession, ReadBsonCommand},
-    request::{
-        self,
-        chat::{LChatListReq, LoginListReq},
-    },
-    response,
+    connector::{tokio::TokioTransport, LocoConnection, LocoConnector},
+    event::{session_events, LocoEvent},
     structs::client::ClientInfo,
 };
-use tokio::io::BufStream;
-use tokio::net::TcpStream;
-use tokio_util::compat::TokioAsyncReadCompatExt;
 
 pub const CONFIG: AuthClientConfig = AuthClientConfig::new_const(
     AuthDeviceConfig::new_const_pc("TEST_DEVICE", ""),
@@ -70,10 +60,6 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let auth_data = auth_res.data.unwrap();
 
-    let loco_session = SecureClientSession::new(
-        RsaPublicKey::from_public_key_der(&pem::parse(KEY)?.contents).unwrap(),
-    );
-
     let client = ClientInfo {
         os: "win32".into(),
         net_type: 0,
@@ -81,92 +67,40 @@ async fn main() -> Result<(), Box<dyn Error>> {
         mccmnc: "999".into(),
     };
 
-    // Skipped booking process
-
-    // Checkin start
-    let checkin_data = {
-        let mut checkin_stream = SecureStream::new(
-            CryptoStore::new(),
-            BufStream::new(
-                TcpStream::connect("ticket-loco.kakao.com:443")
-                    .await
-                    .unwrap(),
-            )
-            .compat(),
-        );
-
-        loco_session.handshake_async(&mut checkin_stream).await?;
-
-        let checkin_res = do_checkin(checkin_stream, client.clone()).await?;
-
-        if checkin_res.data.data.is_none() {
-            println!("CHECKIN failed with status: {}", checkin_res.data.status);
-            return Ok(());
-        }
-        checkin_res.data.data.unwrap()
-    };
-
-    println!("CHECKIN response: {:?}", checkin_data);
-    // Checkin end
-
-    // Login start
-    let mut talk_stream = SecureStream::new(
-        CryptoStore::new(),
-        BufStream::new(
-            TcpStream::connect(&format!("{}:{}", checkin_data.host, checkin_data.port))
-                .await
-                .unwrap(),
-        )
-        .compat(),
+    let connector = LocoConnector::new(
+        TokioTransport::new(SecureClientSession::new(
+            RsaPublicKey::from_public_key_der(&pem::parse(KEY)?.contents).unwrap(),
+        ))?,
+        client,
+        &args[3],
+        auth_data.credential.access_token,
     );
 
-    loco_session.handshake_async(&mut talk_stream).await?;
-
-    let mut talk_conn = BsonCommandSession::new(talk_stream);
-
-    let login_res_data = {
-        let mut talk_client = TalkClient(&mut talk_conn);
-
-        let login_res = talk_client
-            .login(&LoginListReq {
-                client,
-                protocol_version: "1".into(),
-                device_uuid: args[3].clone(),
-                oauth_token: auth_data.credential.access_token,
-                language: "ko".into(),
-                device_type: 2,
-                revision: 0,
-                rp: (),
-                chat_list: LChatListReq {
-                    chat_ids: Vec::new(),
-                    max_ids: Vec::new(),
-                    last_token_id: 0,
-                    last_chat_id: None,
-                },
-                last_block_token: 0,
-                background: false,
-            })
-            .await?;
-
-        if login_res.data.data.is_none() {
-            println!("LOGINLIST failed with status: {}", login_res.data.status);
-            return Ok(());
-        }
+    let LocoConnection {
+        session: mut talk_conn,
+        checkin,
+        login,
+        ..
+    } = connector.connect().await?;
 
-        login_res.data.data.unwrap()
-    };
+    println!("CHECKIN response: {:?}", checkin);
+    println!("LOGINLIST response: {:?}", login);
 
-    println!("LOGINLIST response: {:?}", login_res_data);
-    // Login end
+    // Read incoming broadcast commands
+    let events = session_events(&mut talk_conn);
+    pin_mut!(events);
 
-    loop {
-        // Read incoming broadcast commands
-        let ReadBsonCommand { id, command } = talk_conn.read_async().await?;
+    while let Some(event) = events.next().await {
+        match event? {
+            LocoEvent::Msg(msg) => println!("MSG: {:?}", msg),
 
-        println!("READ {}: {:?}", id, command);
+            LocoEvent::Unknown(command) => println!("READ {}: {:?}", command.method, command),
 
-        thread::sleep(Duration::from_millis(1));
+            event => println!("{}: {:?}", event.method(), event),
+        }
     }
+
+    Ok(())
 }
 
 pub async fn do_login(
@@ -180,22 +114,3 @@ pub async fn do_login(
 
     Ok(auth_client.login(method, true).await?)
 }
-
-pub async fn do_checkin(
-    stream: impl AsyncRead + AsyncWrite + Unpin,
-    client_info: ClientInfo,
-) -> RequestResult<response::checkin::CheckinRes> {
-    let mut conn = BsonCommandSession::new(stream);
-    let mut client = CheckinClient(&mut conn);
-
-    Ok(client
-        .checkin(&request::checkin::CheckinReq {
-            // Should be actual user id. but any numbers work
-            user_id: 1,
-            client: client_info,
-            language: "ko".into(),
-            country_iso: "KR".into(),
-            use_sub: true,
-        })
-        .await?)
-}
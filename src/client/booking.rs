@@ -4,15 +4,19 @@
  * Copyright (c) storycraft. Licensed under the MIT Licence.
  */
 
-use futures::{AsyncRead, AsyncWrite};
+use crate::{request, response};
 
-use crate::{command::session::BsonCommandSession, request, response};
+use super::{client_commands, client_method, data_type, response_type};
 
-use super::client_method;
+#[derive(Debug)]
+pub struct BookingClient<'a, S>(pub &'a mut S);
 
+/// Blocking [BookingClient] for sync streams
 #[derive(Debug)]
-pub struct BookingClient<'a, S>(pub &'a mut BsonCommandSession<S>);
+pub struct BlockingBookingClient<'a, S>(pub &'a mut S);
+
+client_commands! {
+    BookingClient, BlockingBookingClient;
 
-impl<S: AsyncRead + AsyncWrite + Unpin> BookingClient<'_, S> {
-    client_method!(get_conf, "GETCONF", request::booking::GetConfReq => response::booking::GetConfRes);
+    get_conf, "GETCONF", request::booking::GetConfReq => response::booking::GetConfRes;
 }
@@ -4,17 +4,21 @@
  * Copyright (c) storycraft. Licensed under the MIT Licence.
  */
 
-use futures::{AsyncRead, AsyncWrite};
+use crate::{request, response};
 
-use crate::{command::session::BsonCommandSession, request, response};
+use super::{client_commands, client_method, data_type, response_type};
 
-use super::client_method;
+#[derive(Debug)]
+pub struct CheckinClient<'a, S>(pub &'a mut S);
 
+/// Blocking [CheckinClient] for sync streams
 #[derive(Debug)]
-pub struct CheckinClient<'a, S>(pub &'a mut BsonCommandSession<S>);
+pub struct BlockingCheckinClient<'a, S>(pub &'a mut S);
+
+client_commands! {
+    CheckinClient, BlockingCheckinClient;
 
-impl<S: AsyncWrite + AsyncRead + Unpin> CheckinClient<'_, S> {
-    client_method!(checkin, "CHECKIN", request::checkin::CheckinReq => response::checkin::CheckinRes);
+    checkin, "CHECKIN", request::checkin::CheckinReq => response::checkin::CheckinRes;
 
-    client_method!(buy_cs, "BUYCS", request::checkin::BuyCSReq => response::checkin::BuyCSRes);
+    buy_cs, "BUYCS", request::checkin::BuyCSReq => response::checkin::BuyCSRes;
 }
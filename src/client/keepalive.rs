@@ -0,0 +1,178 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{
+    future::{self, Either},
+    pin_mut,
+};
+use futures_timer::Delay;
+
+use crate::{
+    command::{driver::SessionHandle, session::RequestError},
+    request::{self, LocoCommand},
+    structs::connection::ConnectionData,
+};
+
+/// Keepalive intervals
+#[derive(Debug, Clone, Copy)]
+pub struct KeepaliveConfig {
+    /// Ping interval
+    pub interval: Duration,
+
+    /// Ping interval when background
+    pub background_interval: Duration,
+
+    /// Maximum time to wait PING reply
+    pub timeout: Duration,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(20),
+            background_interval: Duration::from_secs(60),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Zero values in server config are replaced with [KeepaliveConfig::default] values
+impl From<&ConnectionData> for KeepaliveConfig {
+    fn from(data: &ConnectionData) -> Self {
+        let default = Self::default();
+
+        Self {
+            interval: non_zero_or(data.ping_interval_duration(), default.interval),
+            background_interval: non_zero_or(
+                data.background_interval_duration(),
+                default.background_interval,
+            ),
+            timeout: non_zero_or(data.request_timeout_duration(), default.timeout),
+        }
+    }
+}
+
+fn non_zero_or(duration: Duration, default: Duration) -> Duration {
+    if duration.is_zero() {
+        default
+    } else {
+        duration
+    }
+}
+
+#[derive(Debug)]
+pub enum KeepaliveError {
+    /// Server did not reply to PING in time
+    ConnectionDead,
+
+    Request(RequestError),
+}
+
+impl Display for KeepaliveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeepaliveError::ConnectionDead => write!(f, "Server did not reply to PING"),
+            KeepaliveError::Request(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for KeepaliveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KeepaliveError::Request(err) => Some(err),
+            KeepaliveError::ConnectionDead => None,
+        }
+    }
+}
+
+/// Cloneable switch for changing [Keepalive] interval
+#[derive(Debug, Clone)]
+pub struct KeepaliveControl {
+    background: Arc<AtomicBool>,
+}
+
+impl KeepaliveControl {
+    pub fn background(&self) -> bool {
+        self.background.load(Ordering::Relaxed)
+    }
+
+    /// Use background interval from next PING if true
+    pub fn set_background(&self, background: bool) {
+        self.background.store(background, Ordering::Relaxed);
+    }
+}
+
+/// Sends PING periodically through [SessionHandle].
+/// The keepalive does nothing until [Keepalive::run] future is polled.
+#[derive(Debug)]
+pub struct Keepalive {
+    handle: SessionHandle,
+    config: KeepaliveConfig,
+
+    background: Arc<AtomicBool>,
+}
+
+impl Keepalive {
+    pub fn new(handle: SessionHandle, config: KeepaliveConfig) -> Self {
+        Self {
+            handle,
+            config,
+
+            background: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn control(&self) -> KeepaliveControl {
+        KeepaliveControl {
+            background: self.background.clone(),
+        }
+    }
+
+    /// Run keepalive.
+    /// Returns Ok if session is stopped, [KeepaliveError::ConnectionDead] if PING is not replied.
+    pub async fn run(self) -> Result<(), KeepaliveError> {
+        let closed = self.handle.closed();
+        pin_mut!(closed);
+
+        loop {
+            let interval = if self.background.load(Ordering::Relaxed) {
+                self.config.background_interval
+            } else {
+                self.config.interval
+            };
+
+            if let Either::Right(_) = future::select(Delay::new(interval), closed.as_mut()).await {
+                return Ok(());
+            }
+
+            let res = self
+                .handle
+                .request_with_timeout(&request::Ping {}.to_command(), Some(self.config.timeout))
+                .await;
+
+            match res {
+                Ok(_) => {}
+
+                Err(err) if err.is_closed() => return Ok(()),
+
+                Err(err) if err.is_timeout() => return Err(KeepaliveError::ConnectionDead),
+
+                Err(err) => return Err(KeepaliveError::Request(err)),
+            }
+        }
+    }
+}
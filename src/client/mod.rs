@@ -8,6 +8,8 @@ pub mod booking;
 pub mod checkin;
 pub mod talk;
 
+pub mod keepalive;
+
 pub mod media;
 
 use std::{
@@ -21,51 +23,69 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     command::{
-        codec::{ReadError, WriteError},
-        session::BsonCommandSession,
-        BsonCommand,
+        codec::deserialize_command, driver::SessionHandle, session::BsonCommandSession,
+        BsonCommand, ReadBsonCommand,
     },
+    request::LocoCommand,
     response::ResponseData,
+    structs::client::Status,
 };
 
+pub use crate::command::session::{RequestError, RequestErrorKind};
+
+pub type RequestResult<T> = Result<BsonCommand<ResponseData<T>>, RequestError>;
+
+/// Request error including non success response status
 #[derive(Debug)]
-pub enum RequestError {
-    Write(WriteError),
-    Read(ReadError),
-    Deserialize(bson::de::Error),
-}
+pub enum LocoError {
+    Request(RequestError),
 
-impl From<WriteError> for RequestError {
-    fn from(err: WriteError) -> Self {
-        Self::Write(err)
-    }
+    /// Server responded with non success status
+    Status(Status),
+
+    /// Server responded with success status but without data
+    MissingData,
 }
 
-impl From<ReadError> for RequestError {
-    fn from(err: ReadError) -> Self {
-        Self::Read(err)
+impl From<RequestError> for LocoError {
+    fn from(err: RequestError) -> Self {
+        Self::Request(err)
     }
 }
 
-impl From<bson::de::Error> for RequestError {
-    fn from(err: bson::de::Error) -> Self {
-        Self::Deserialize(err)
+impl Display for LocoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocoError::Request(err) => err.fmt(f),
+            LocoError::Status(status) => {
+                write!(
+                    f,
+                    "Request failed with status: {:?}({})",
+                    status,
+                    status.code()
+                )
+            }
+            LocoError::MissingData => write!(f, "Response data is missing"),
+        }
     }
 }
 
-impl Display for RequestError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Error for LocoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            RequestError::Write(err) => err.fmt(f),
-            RequestError::Read(err) => err.fmt(f),
-            RequestError::Deserialize(err) => err.fmt(f),
+            LocoError::Request(err) => Some(err),
+            _ => None,
         }
     }
 }
 
-impl Error for RequestError {}
+pub type LocoResult<T> = Result<T, LocoError>;
 
-pub type RequestResult<T> = Result<BsonCommand<ResponseData<T>>, RequestError>;
+/// Unwrap [RequestResult] data.
+/// Non success status and missing data are mapped to [LocoError].
+pub fn into_data<T>(result: RequestResult<T>) -> LocoResult<T> {
+    result?.data.into_result()
+}
 
 /// Convenience method for requesting command
 #[inline]
@@ -73,8 +93,13 @@ pub fn request_response<D: DeserializeOwned>(
     session: &mut BsonCommandSession<impl Read + Write>,
     command: &BsonCommand<impl Serialize>,
 ) -> RequestResult<D> {
-    let req = session.request(command)?;
-    Ok(session.response(req)?.try_deserialize()?)
+    let req = session
+        .request_with_id(command)
+        .map_err(|(request_id, err)| request_error(command, request_id, err))?;
+
+    session
+        .response_typed(req)
+        .map_err(|err| request_error(command, Some(req), err))
 }
 
 /// Convenience method for requesting command asynchronously
@@ -82,31 +107,202 @@ pub async fn request_response_async<D: DeserializeOwned>(
     session: &mut BsonCommandSession<impl AsyncRead + AsyncWrite + Unpin>,
     command: &BsonCommand<impl Serialize>,
 ) -> RequestResult<D> {
-    let req = session.request_async(command).await?;
-    Ok(session.response_async(req).await?.try_deserialize()?)
+    let req = session
+        .request_with_id_async(command)
+        .await
+        .map_err(|(request_id, err)| request_error(command, request_id, err))?;
+
+    session
+        .response_typed_async(req)
+        .await
+        .map_err(|err| request_error(command, Some(req), err))
+}
+
+/// Convenience method for requesting command using [SessionHandle]
+pub async fn request_response_handle<D: DeserializeOwned>(
+    handle: &SessionHandle,
+    command: &BsonCommand<impl Serialize>,
+) -> RequestResult<D> {
+    let ReadBsonCommand {
+        id,
+        command: response,
+    } = handle
+        .send_request(command, handle.timeout())
+        .await
+        .map_err(|err| err.with_method(command.method.clone()))?;
+
+    deserialize_command(response).map_err(|err| request_error(command, Some(id), err))
+}
+
+/// Attach request context to error
+fn request_error<T>(
+    command: &BsonCommand<T>,
+    request_id: Option<i32>,
+    err: impl Into<RequestError>,
+) -> RequestError {
+    let err = err.into().with_method(command.method.clone());
+
+    match request_id {
+        Some(request_id) => err.with_request_id(request_id),
+        None => err,
+    }
+}
+
+impl<S: Read + Write> BsonCommandSession<S> {
+    /// Request [LocoCommand] and read its response
+    pub fn send<C: LocoCommand>(&mut self, command: &C) -> RequestResult<C::Response> {
+        request_response(self, &command.to_command())
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> BsonCommandSession<S> {
+    /// Request [LocoCommand] and read its response asynchronously
+    pub async fn send_async<C: LocoCommand>(&mut self, command: &C) -> RequestResult<C::Response> {
+        request_response_async(self, &command.to_command()).await
+    }
+}
+
+impl SessionHandle {
+    /// Request [LocoCommand] and wait for its response
+    pub async fn send<C: LocoCommand>(&self, command: &C) -> RequestResult<C::Response> {
+        request_response_handle(self, &command.to_command()).await
+    }
 }
 
 macro_rules! client_method {
     (
+        blocking $request_fn: path;
         $(#[$meta:meta])*
-        $name: ident, $method: literal, $request: ty => $response: ty
+        $name: ident, $method: literal, $data_type: expr, $request: ty => $response: ty
     ) => {
         $(#[$meta])*
-        pub async fn $name(
+        pub fn $name(
             &mut self,
             command: &$request,
-        ) -> crate::client::RequestResult<$response> {
-            crate::client::request_response_async(
+        ) -> crate::client::LocoResult<$response> {
+            crate::client::into_data($request_fn(
                 self.0,
-                &crate::command::BsonCommand::new_const($method, 0, command),
+                &crate::command::BsonCommand::new_const($method, $data_type, command),
+            ))
+        }
+    };
+
+    (
+        blocking $request_fn: path;
+        $(#[$meta:meta])*
+        $name: ident, $method: literal, $data_type: expr, $request: ty
+    ) => {
+        client_method!(blocking $request_fn; $(#[$meta])* $name, $method, $data_type, $request => ());
+    };
+
+    (
+        $request_fn: path;
+        $(#[$meta:meta])*
+        $name: ident, $method: literal, $data_type: expr, $request: ty => $response: ty
+    ) => {
+        $(#[$meta])*
+        pub async fn $name(
+            &mut self,
+            command: &$request,
+        ) -> crate::client::LocoResult<$response> {
+            crate::client::into_data(
+                $request_fn(
+                    self.0,
+                    &crate::command::BsonCommand::new_const($method, $data_type, command),
+                )
+                .await,
             )
-            .await
         }
     };
 
-    ($name: ident, $method: literal, $request: ty) => {
-        client_method!($name, $method, $request => ());
+    (
+        $request_fn: path;
+        $(#[$meta:meta])*
+        $name: ident, $method: literal, $data_type: expr, $request: ty
+    ) => {
+        client_method!($request_fn; $(#[$meta])* $name, $method, $data_type, $request => ());
+    };
+}
+
+/// Command data type. 0 if not declared.
+macro_rules! data_type {
+    () => {
+        0
+    };
+
+    ($data_type: literal) => {
+        $data_type
+    };
+}
+
+/// Command response type. () if not declared.
+macro_rules! response_type {
+    () => {
+        ()
+    };
+
+    ($response: ty) => {
+        $response
+    };
+}
+
+/// Implement [LocoCommand] for requests and client methods for [BsonCommandSession] and [SessionHandle].
+/// Blocking client methods are implemented for [BsonCommandSession] using sync stream.
+/// Client methods return response data. Non success status and missing data are mapped to [LocoError].
+/// Append `, data_type = N` to command to declare data type.
+macro_rules! client_commands {
+    (
+        $client: ident, $blocking_client: ident;
+        $(
+            $(#[$meta:meta])*
+            $name: ident, $method: literal, $request: ty $(=> $response: ty)?
+            $(, data_type = $data_type: literal)?;
+        )*
+    ) => {
+        $(
+            impl crate::request::LocoCommand for $request {
+                const METHOD: &'static str = $method;
+
+                const DATA_TYPE: i8 = data_type!($($data_type)?);
+
+                type Response = response_type!($($response)?);
+            }
+        )*
+
+        impl<S: futures::AsyncRead + futures::AsyncWrite + Unpin>
+            $client<'_, crate::command::session::BsonCommandSession<S>>
+        {
+            $(
+                client_method!(
+                    crate::client::request_response_async;
+                    $(#[$meta])* $name, $method, data_type!($($data_type)?), $request $(=> $response)?
+                );
+            )*
+        }
+
+        impl $client<'_, crate::command::driver::SessionHandle> {
+            $(
+                client_method!(
+                    crate::client::request_response_handle;
+                    $(#[$meta])* $name, $method, data_type!($($data_type)?), $request $(=> $response)?
+                );
+            )*
+        }
+
+        impl<S: std::io::Read + std::io::Write>
+            $blocking_client<'_, crate::command::session::BsonCommandSession<S>>
+        {
+            $(
+                client_method!(
+                    blocking crate::client::request_response;
+                    $(#[$meta])* $name, $method, data_type!($($data_type)?), $request $(=> $response)?
+                );
+            )*
+        }
     };
 }
 
+use client_commands;
 use client_method;
+use data_type;
+use response_type;
@@ -7,18 +7,21 @@
 use std::{
     error::Error,
     fmt::Display,
-    io::{Cursor, Read, Write},
+    io::{self, Cursor, Read, Write},
+    mem,
+    pin::Pin,
     string::FromUtf8Error,
+    task::{Context, Poll},
 };
 
 use bson::Document;
-use futures::{io::Flush, AsyncRead, AsyncWrite, AsyncWriteExt};
+use futures::{future, ready, AsyncRead, AsyncWrite};
 use loco_protocol::command::{
     builder::CommandBuilder,
     codec::{CommandCodec, StreamError},
     Command,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 
 use super::{BsonCommand, ReadBsonCommand};
 
@@ -26,6 +29,9 @@ use super::{BsonCommand, ReadBsonCommand};
 pub enum WriteError {
     Codec(StreamError),
     Encode(bson::ser::Error),
+
+    /// Every request id is in flight
+    IdExhausted,
 }
 
 impl From<StreamError> for WriteError {
@@ -45,6 +51,7 @@ impl Display for WriteError {
         match self {
             WriteError::Codec(err) => err.fmt(f),
             WriteError::Encode(err) => err.fmt(f),
+            WriteError::IdExhausted => write!(f, "No request id available"),
         }
     }
 }
@@ -60,6 +67,21 @@ pub enum ReadError {
 
     InvalidMethod(FromUtf8Error),
     Decode(bson::de::Error),
+
+    /// Data is valid bson document but does not match expected type
+    Deserialize {
+        error: bson::de::Error,
+        data: Document,
+    },
+
+    /// Unsolicited command buffer of session is full
+    BufferFull,
+
+    /// Response did not arrive in session timeout
+    Timeout,
+
+    /// Request is not waiting for response. Its response is already returned or discarded.
+    NotOutstanding,
 }
 
 impl From<StreamError> for ReadError {
@@ -89,16 +111,46 @@ impl Display for ReadError {
             }
             ReadError::InvalidMethod(err) => err.fmt(f),
             ReadError::Decode(err) => err.fmt(f),
+            ReadError::Deserialize { error, .. } => error.fmt(f),
+            ReadError::BufferFull => write!(f, "Command buffer is full"),
+            ReadError::Timeout => write!(f, "Response timed out"),
+            ReadError::NotOutstanding => write!(f, "Response is already returned or discarded"),
         }
     }
 }
 
 impl Error for ReadError {}
 
-/// [BsonCommand] codec
+/// Size of command header
+const HEADER_SIZE: usize = 22;
+
+/// Offset of data size in command header
+const DATA_SIZE_OFFSET: usize = 18;
+
+/// Size of read chunk
+const READ_CHUNK_SIZE: usize = 1024;
+
+/// Read and write buffers of [BsonCommandCodec]
+#[derive(Debug, Default)]
+pub(crate) struct CodecBuffers {
+    pub read_buf: Vec<u8>,
+    pub write_buf: Vec<u8>,
+}
+
+impl CodecBuffers {
+    pub fn is_empty(&self) -> bool {
+        self.read_buf.is_empty() && self.write_buf.is_empty()
+    }
+}
+
+/// [BsonCommand] codec.
+/// Async methods are cancellation safe. Partially read frame and unwritten data are kept between calls.
 #[derive(Debug)]
 pub struct BsonCommandCodec<S> {
     inner_codec: CommandCodec<S>,
+
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
 }
 
 impl<S> BsonCommandCodec<S> {
@@ -106,6 +158,9 @@ impl<S> BsonCommandCodec<S> {
     pub fn new(stream: S) -> Self {
         Self {
             inner_codec: CommandCodec::new(stream),
+
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
         }
     }
 
@@ -117,9 +172,58 @@ impl<S> BsonCommandCodec<S> {
         self.inner_codec.stream_mut()
     }
 
+    /// Consume self and returns inner stream.
+    /// Partially read frame and unwritten data are dropped.
     pub fn into_inner(self) -> S {
         self.inner_codec.into_inner()
     }
+
+    /// Create codec continuing from buffers of other codec
+    pub(crate) fn from_parts(stream: S, buffers: CodecBuffers) -> Self {
+        Self {
+            inner_codec: CommandCodec::new(stream),
+
+            read_buf: buffers.read_buf,
+            write_buf: buffers.write_buf,
+        }
+    }
+
+    /// Consume self and returns inner stream with buffers
+    pub(crate) fn into_parts(self) -> (S, CodecBuffers) {
+        let buffers = CodecBuffers {
+            read_buf: self.read_buf,
+            write_buf: self.write_buf,
+        };
+
+        (self.inner_codec.into_inner(), buffers)
+    }
+
+    /// Take complete frame from read buffer
+    fn take_frame(&mut self) -> Result<Option<Command>, ReadError> {
+        take_frame(&mut self.read_buf)
+    }
+
+    /// Size of next read which does not exceed current frame
+    fn next_read_size(&self) -> usize {
+        (frame_size(&self.read_buf) - self.read_buf.len()).min(READ_CHUNK_SIZE)
+    }
+
+    /// Returns true if part of next frame is read
+    pub(crate) fn has_partial_frame(&self) -> bool {
+        !self.read_buf.is_empty()
+    }
+
+    /// Encode [BsonCommand] to write buffer
+    pub(crate) fn start_write(
+        &mut self,
+        request_id: i32,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<(), WriteError> {
+        let command = encode_bson_command(request_id, command)?;
+        CommandCodec::new(&mut self.write_buf).write(&command)?;
+
+        Ok(())
+    }
 }
 
 impl<S: Write> BsonCommandCodec<S> {
@@ -130,13 +234,23 @@ impl<S: Write> BsonCommandCodec<S> {
         command: &BsonCommand<impl Serialize>,
     ) -> Result<(), WriteError> {
         let command = encode_bson_command(request_id, command)?;
+
+        // Data left by cancelled async write
+        if !self.write_buf.is_empty() {
+            self.inner_codec
+                .stream_mut()
+                .write_all(&self.write_buf)
+                .map_err(StreamError::Io)?;
+            self.write_buf.clear();
+        }
+
         self.inner_codec.write(&command)?;
 
         Ok(())
     }
 
     /// Flush inner stream
-    pub fn flush(&mut self) -> std::io::Result<()> {
+    pub fn flush(&mut self) -> io::Result<()> {
         self.inner_codec.stream_mut().flush()
     }
 }
@@ -144,20 +258,35 @@ impl<S: Write> BsonCommandCodec<S> {
 impl<S: Read> BsonCommandCodec<S> {
     /// Read incoming [BsonCommand]
     pub fn read(&mut self) -> Result<ReadBsonCommand<Document>, ReadError> {
-        let (_, command) = self.inner_codec.read()?;
+        decode_command(self.read_raw()?)
+    }
 
-        if command.header.status == 0 {
-            let id = command.header.id;
-            let method = command.header.method()?;
+    /// Read incoming [BsonCommand] deserializing data directly from frame
+    pub fn read_typed<D: DeserializeOwned>(&mut self) -> Result<ReadBsonCommand<D>, ReadError> {
+        decode_command(self.read_raw()?)
+    }
 
-            let data = bson::Document::from_reader(&mut Cursor::new(command.data))?;
+    /// Read incoming [Command] without decoding data
+    pub fn read_raw(&mut self) -> Result<Command, ReadError> {
+        loop {
+            if let Some(command) = self.take_frame()? {
+                return Ok(command);
+            }
 
-            Ok(ReadBsonCommand {
-                id,
-                command: BsonCommand::new(method, command.header.data_type, data),
-            })
-        } else {
-            Err(ReadError::Corrupted(command))
+            let mut chunk = [0; READ_CHUNK_SIZE];
+            let size = self.next_read_size();
+
+            let read = self
+                .inner_codec
+                .stream_mut()
+                .read(&mut chunk[..size])
+                .map_err(StreamError::Io)?;
+
+            if read == 0 {
+                return Err(StreamError::Io(io::ErrorKind::UnexpectedEof.into()).into());
+            }
+
+            self.read_buf.extend_from_slice(&chunk[..read]);
         }
     }
 }
@@ -169,39 +298,168 @@ impl<S: AsyncWrite + Unpin> BsonCommandCodec<S> {
         request_id: i32,
         command: &BsonCommand<impl Serialize>,
     ) -> Result<(), WriteError> {
-        let command = encode_bson_command(request_id, command)?;
-        self.inner_codec.write_async(&command).await?;
+        self.start_write(request_id, command)?;
 
-        Ok(())
+        future::poll_fn(|cx| self.poll_write_buffered(cx))
+            .await
+            .map_err(|err| WriteError::Codec(StreamError::Io(err)))
     }
 
     /// Flush inner stream async
-    pub fn flush_async(&mut self) -> Flush<'_, S> {
-        self.inner_codec.stream_mut().flush()
+    pub async fn flush_async(&mut self) -> io::Result<()> {
+        future::poll_fn(|cx| self.poll_flush(cx)).await
+    }
+
+    /// Write encoded data. Data not written yet are kept if cancelled.
+    pub(crate) fn poll_write_buffered(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.write_buf.is_empty() {
+            let written =
+                ready!(Pin::new(self.inner_codec.stream_mut()).poll_write(cx, &self.write_buf))?;
+
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.write_buf.drain(..written);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    pub(crate) fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buffered(cx))?;
+        Pin::new(self.inner_codec.stream_mut()).poll_flush(cx)
+    }
+
+    pub(crate) fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_write_buffered(cx))?;
+        Pin::new(self.inner_codec.stream_mut()).poll_close(cx)
     }
 }
 
 impl<S: AsyncRead + Unpin> BsonCommandCodec<S> {
     /// Read incoming [BsonCommand]
     pub async fn read_async(&mut self) -> Result<ReadBsonCommand<Document>, ReadError> {
-        let (_, command) = self.inner_codec.read_async().await?;
+        decode_command(self.read_raw_async().await?)
+    }
 
-        if command.header.status == 0 {
-            let id = command.header.id;
-            let method = command.header.method()?;
+    /// Read incoming [BsonCommand] deserializing data directly from frame
+    pub async fn read_typed_async<D: DeserializeOwned>(
+        &mut self,
+    ) -> Result<ReadBsonCommand<D>, ReadError> {
+        decode_command(self.read_raw_async().await?)
+    }
 
-            let data = bson::Document::from_reader(&mut Cursor::new(command.data))?;
+    /// Read incoming [Command] without decoding data.
+    /// Partially read frame is kept if cancelled.
+    pub async fn read_raw_async(&mut self) -> Result<Command, ReadError> {
+        future::poll_fn(|cx| self.poll_read_raw(cx)).await
+    }
 
-            Ok(ReadBsonCommand {
-                id,
-                command: BsonCommand::new(method, command.header.data_type, data),
-            })
-        } else {
-            Err(ReadError::Corrupted(command))
+    pub(crate) fn poll_read_raw(&mut self, cx: &mut Context<'_>) -> Poll<Result<Command, ReadError>> {
+        loop {
+            if let Some(command) = self.take_frame()? {
+                return Poll::Ready(Ok(command));
+            }
+
+            let mut chunk = [0; READ_CHUNK_SIZE];
+            let size = self.next_read_size();
+
+            let read = ready!(
+                Pin::new(self.inner_codec.stream_mut()).poll_read(cx, &mut chunk[..size])
+            )
+            .map_err(StreamError::Io)?;
+
+            if read == 0 {
+                return Poll::Ready(Err(
+                    StreamError::Io(io::ErrorKind::UnexpectedEof.into()).into()
+                ));
+            }
+
+            self.read_buf.extend_from_slice(&chunk[..read]);
         }
     }
 }
 
+/// Size of buffered frame including header.
+/// Returns header size if header is not read yet.
+fn frame_size(buf: &[u8]) -> usize {
+    match buf.get(DATA_SIZE_OFFSET..HEADER_SIZE) {
+        Some(data_size) => HEADER_SIZE + u32::from_le_bytes(data_size.try_into().unwrap()) as usize,
+
+        None => HEADER_SIZE,
+    }
+}
+
+/// Take complete frame from start of buffer
+pub(crate) fn take_frame(buf: &mut Vec<u8>) -> Result<Option<Command>, ReadError> {
+    match take_raw_frame(buf) {
+        Some(frame) => Ok(Some(parse_frame(&frame)?)),
+        None => Ok(None),
+    }
+}
+
+/// Take complete frame from start of buffer without parsing
+pub(crate) fn take_raw_frame(buf: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let frame_size = frame_size(buf);
+    if buf.len() < frame_size {
+        return None;
+    }
+
+    let rest = buf.split_off(frame_size);
+    Some(mem::replace(buf, rest))
+}
+
+/// Parse complete raw frame
+pub(crate) fn parse_frame(frame: &[u8]) -> Result<Command, ReadError> {
+    let (_, command) = CommandCodec::new(Cursor::new(frame)).read()?;
+
+    Ok(command)
+}
+
+/// Decode [Command] data without intermediate [Document]
+pub fn decode_command<D: DeserializeOwned>(
+    command: Command,
+) -> Result<ReadBsonCommand<D>, ReadError> {
+    if command.header.status == 0 {
+        let id = command.header.id;
+        let method = command.header.method()?;
+
+        let data = bson::from_slice(&command.data).map_err(|error| {
+            match Document::from_reader(command.data.as_slice()) {
+                Ok(data) => ReadError::Deserialize { error, data },
+                Err(_) => ReadError::Decode(error),
+            }
+        })?;
+
+        Ok(ReadBsonCommand {
+            id,
+            command: BsonCommand::new(method, command.header.data_type, data),
+        })
+    } else {
+        Err(ReadError::Corrupted(command))
+    }
+}
+
+/// Deserialize [Document] data of [BsonCommand].
+/// Fails with [ReadError::Deserialize] keeping the data.
+pub fn deserialize_command<D: DeserializeOwned>(
+    command: BsonCommand<Document>,
+) -> Result<BsonCommand<D>, ReadError> {
+    match bson::from_document(command.data.clone()) {
+        Ok(data) => Ok(BsonCommand {
+            method: command.method,
+            data_type: command.data_type,
+            data,
+        }),
+
+        Err(error) => Err(ReadError::Deserialize {
+            error,
+            data: command.data,
+        }),
+    }
+}
+
 fn encode_bson_command(
     request_id: i32,
     command: &BsonCommand<impl Serialize>,
@@ -213,5 +471,52 @@ fn encode_bson_command(
     let doc = bson::to_document(&command.data)?;
     doc.to_writer(&mut raw_data)?;
 
-    Ok(builder.build(0, raw_data))
+    Ok(builder.build(command.data_type, raw_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::doc;
+
+    use crate::command::BsonCommand;
+
+    use super::BsonCommandCodec;
+
+    #[test]
+    fn write_read_round_trip() {
+        let data = doc! { "chatId": 1_i64 };
+
+        let mut codec = BsonCommandCodec::new(Vec::new());
+        codec
+            .write(7, &BsonCommand::new_const("MSG", 2, data.clone()))
+            .unwrap();
+
+        let mut codec = BsonCommandCodec::new(std::io::Cursor::new(codec.into_inner()));
+        let read = codec.read().unwrap();
+
+        assert_eq!(read.id, 7);
+        assert_eq!(read.command.method, "MSG");
+        assert_eq!(read.command.data_type, 2);
+        assert_eq!(read.command.data, data);
+    }
+
+    #[tokio::test]
+    async fn write_read_round_trip_async() {
+        let data = doc! { "chatId": 1_i64 };
+
+        let mut codec = BsonCommandCodec::new(futures::io::Cursor::new(Vec::new()));
+        codec
+            .write_async(7, &BsonCommand::new_const("MSG", 2, data.clone()))
+            .await
+            .unwrap();
+
+        let mut codec =
+            BsonCommandCodec::new(futures::io::Cursor::new(codec.into_inner().into_inner()));
+        let read = codec.read_async().await.unwrap();
+
+        assert_eq!(read.id, 7);
+        assert_eq!(read.command.method, "MSG");
+        assert_eq!(read.command.data_type, 2);
+        assert_eq!(read.command.data, data);
+    }
 }
@@ -0,0 +1,492 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bson::Document;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, Either, Shared},
+    pin_mut, AsyncRead, AsyncReadExt, AsyncWrite, Future, FutureExt, StreamExt,
+};
+use futures_timer::Delay;
+use loco_protocol::command::codec::StreamError;
+use serde::Serialize;
+
+use super::{
+    codec::{BsonCommandCodec, CodecBuffers, ReadError, WriteError},
+    id::IdAllocator,
+    interceptor::{Interceptor, InterceptorChain},
+    session::{BsonCommandSession, RequestError, SessionParts},
+    BsonCommand, ReadBsonCommand,
+};
+
+type ResponseSender = oneshot::Sender<Result<ReadBsonCommand<Document>, RequestError>>;
+
+/// Receiver of commands which are not response of any request
+pub type BroadcastReceiver = mpsc::UnboundedReceiver<ReadBsonCommand<Document>>;
+
+pub(crate) type RequestReceiver = mpsc::UnboundedReceiver<PendingRequest>;
+
+#[derive(Debug)]
+pub(crate) struct PendingRequest {
+    command: BsonCommand<Document>,
+    sender: ResponseSender,
+
+    /// Notified with allocated request id
+    id_sender: oneshot::Sender<i32>,
+}
+
+/// Requests waiting for response in [SessionDriver]
+#[derive(Debug, Default)]
+struct PendingMap {
+    senders: HashMap<i32, ResponseSender>,
+
+    /// Requests given up by cancel or timeout. Their late responses are discarded on arrival.
+    abandoned: HashSet<i32>,
+}
+
+impl PendingMap {
+    /// Abandon requests whose waiting side is dropped
+    fn abandon_cancelled(&mut self) {
+        let abandoned = &mut self.abandoned;
+
+        self.senders.retain(|id, sender| {
+            if sender.is_canceled() {
+                abandoned.insert(*id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn in_flight(&self, id: i32) -> bool {
+        self.senders.contains_key(&id) || self.abandoned.contains(&id)
+    }
+
+    /// Returns true if id is abandoned and its response should be discarded
+    fn discard_abandoned(&mut self, id: i32) -> bool {
+        self.abandoned.remove(&id)
+    }
+}
+
+/// Default request timeout shared by every clone of [SessionHandle]
+pub(crate) type SharedTimeout = Arc<Mutex<Option<Duration>>>;
+
+/// Cheap cloneable handle for sending request to [SessionDriver].
+/// Every clone can request concurrently.
+#[derive(Debug, Clone)]
+pub struct SessionHandle {
+    sender: mpsc::UnboundedSender<PendingRequest>,
+    closed: Shared<oneshot::Receiver<()>>,
+
+    timeout: SharedTimeout,
+}
+
+impl SessionHandle {
+    /// Create handle with receiving end of its requests.
+    /// Handles are notified as closed when returned [oneshot::Sender] is dropped.
+    pub(crate) fn channel() -> (Self, RequestReceiver, oneshot::Sender<()>) {
+        let (sender, receiver) = mpsc::unbounded();
+        let (closed_sender, closed) = oneshot::channel();
+
+        (
+            Self {
+                sender,
+                closed: closed.shared(),
+                timeout: SharedTimeout::default(),
+            },
+            receiver,
+            closed_sender,
+        )
+    }
+
+    /// Pass request received from other handle to driver of this handle.
+    /// Dropping request fails it with [super::session::RequestErrorKind::Closed].
+    pub(crate) fn forward(&self, request: PendingRequest) {
+        self.sender.unbounded_send(request).ok();
+    }
+
+    /// Default request timeout. None if requests wait forever.
+    pub fn timeout(&self) -> Option<Duration> {
+        *self.timeout.lock().unwrap()
+    }
+
+    /// Set default request timeout of this handle and its clones.
+    /// Use [crate::structs::connection::ConnectionData::request_timeout_duration] for server provided value.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        *self.timeout.lock().unwrap() = timeout;
+    }
+
+    pub(crate) fn shared_timeout(&self) -> SharedTimeout {
+        self.timeout.clone()
+    }
+
+    /// Send request and wait for its response using default timeout.
+    /// Returns [super::session::RequestErrorKind::Closed] if driver is stopped before response arrives.
+    pub async fn request(
+        &self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<BsonCommand<Document>, RequestError> {
+        self.request_with_timeout(command, self.timeout()).await
+    }
+
+    /// Send request and wait for its response.
+    /// Returns [super::session::RequestErrorKind::Timeout] if response does not arrive in given timeout.
+    pub async fn request_with_timeout(
+        &self,
+        command: &BsonCommand<impl Serialize>,
+        timeout: Option<Duration>,
+    ) -> Result<BsonCommand<Document>, RequestError> {
+        Ok(self.send_request(command, timeout).await?.command)
+    }
+
+    /// Send request and wait for its response with request id.
+    /// Errors have request id attached if the request is sent.
+    pub(crate) async fn send_request(
+        &self,
+        command: &BsonCommand<impl Serialize>,
+        timeout: Option<Duration>,
+    ) -> Result<ReadBsonCommand<Document>, RequestError> {
+        let data = bson::to_document(&command.data).map_err(WriteError::from)?;
+
+        let (sender, receiver) = oneshot::channel();
+        let (id_sender, mut id_receiver) = oneshot::channel();
+
+        self.sender
+            .unbounded_send(PendingRequest {
+                command: BsonCommand {
+                    method: command.method.clone(),
+                    data_type: command.data_type,
+                    data,
+                },
+                sender,
+                id_sender,
+            })
+            .map_err(|_| RequestError::closed())?;
+
+        let res = match timeout {
+            Some(timeout) => match future::select(receiver, Delay::new(timeout)).await {
+                Either::Left((res, _)) => res.unwrap_or_else(|_| Err(RequestError::closed())),
+                Either::Right(_) => Err(RequestError::timeout()),
+            },
+
+            None => receiver
+                .await
+                .unwrap_or_else(|_| Err(RequestError::closed())),
+        };
+
+        res.map_err(|err| match id_receiver.try_recv() {
+            Ok(Some(request_id)) if err.request_id().is_none() => err.with_request_id(request_id),
+            _ => err,
+        })
+    }
+
+    /// Returns true if driver is stopped
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Future resolves when driver is stopped or dropped
+    pub fn closed(&self) -> impl Future<Output = ()> {
+        self.closed.clone().map(|_| ())
+    }
+}
+
+/// Session driver owning stream.
+/// Routes each response to its waiting request and pass other commands to [BroadcastReceiver].
+///
+/// The driver does nothing until [SessionDriver::run] future is polled.
+/// Spawn it using any async runtime.
+#[derive(Debug)]
+pub struct SessionDriver<S> {
+    stream: S,
+    buffers: CodecBuffers,
+
+    id_allocator: IdAllocator,
+    interceptors: InterceptorChain,
+
+    receiver: RequestReceiver,
+    broadcast_sender: mpsc::UnboundedSender<ReadBsonCommand<Document>>,
+
+    closed_sender: oneshot::Sender<()>,
+}
+
+impl<S> SessionDriver<S> {
+    /// Create new [SessionDriver] with its [SessionHandle] and [BroadcastReceiver]
+    pub fn new(stream: S) -> (Self, SessionHandle, BroadcastReceiver) {
+        let (handle, receiver, closed_sender) = SessionHandle::channel();
+        let (broadcast_sender, broadcast_receiver) = mpsc::unbounded();
+
+        (
+            Self {
+                stream,
+                buffers: CodecBuffers::default(),
+
+                id_allocator: IdAllocator::default(),
+                interceptors: InterceptorChain::new(),
+                receiver,
+                broadcast_sender,
+                closed_sender,
+            },
+            handle,
+            broadcast_receiver,
+        )
+    }
+
+    /// Create [SessionDriver] continuing [BsonCommandSession].
+    /// Request id allocator, interceptors, timeout and partially read or written data are kept.
+    /// Commands buffered by session are passed to [BroadcastReceiver] first.
+    pub fn from_session(
+        session: BsonCommandSession<S>,
+    ) -> (Self, SessionHandle, BroadcastReceiver) {
+        let SessionParts {
+            id_allocator,
+            read_map,
+            interceptors,
+            timeout,
+            stream,
+            buffers,
+        } = session.into_parts();
+
+        let (mut driver, mut handle, broadcast_receiver) = Self::new(stream);
+        driver.buffers = buffers;
+        driver.id_allocator = id_allocator;
+        driver.interceptors = interceptors;
+        handle.set_timeout(timeout);
+
+        for (id, command) in read_map {
+            driver
+                .broadcast_sender
+                .unbounded_send(ReadBsonCommand { id, command })
+                .ok();
+        }
+
+        (driver, handle, broadcast_receiver)
+    }
+
+    /// Replace [IdAllocator]. Use it to start from given id or use custom strategy.
+    pub fn set_id_allocator(&mut self, id_allocator: IdAllocator) {
+        self.id_allocator = id_allocator;
+    }
+
+    /// Append [Interceptor] to end of interceptor chain.
+    /// Interceptors see every request sent by handles and every command read.
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub fn interceptors(&self) -> &InterceptorChain {
+        &self.interceptors
+    }
+
+    pub fn interceptors_mut(&mut self) -> &mut InterceptorChain {
+        &mut self.interceptors
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SessionDriver<S> {
+    /// Run driver until the stream fails.
+    /// Stops with error if data left by converted session cannot be written.
+    /// Pending requests fail with [super::session::RequestErrorKind::Closed] after driver is stopped.
+    pub async fn run(self) -> Result<(), ReadError> {
+        let Self {
+            stream,
+            buffers,
+            mut id_allocator,
+            interceptors,
+            mut receiver,
+            broadcast_sender,
+            // Notify handles on drop
+            closed_sender: _closed_sender,
+        } = self;
+
+        let (read_half, write_half) = stream.split();
+        let CodecBuffers {
+            read_buf,
+            write_buf,
+        } = buffers;
+
+        let pending_map = Mutex::new(PendingMap::default());
+        let interceptors = Mutex::new(interceptors);
+
+        let write_task = async {
+            let mut codec = BsonCommandCodec::from_parts(
+                write_half,
+                CodecBuffers {
+                    read_buf: Vec::new(),
+                    write_buf,
+                },
+            );
+
+            // Write data left by session. Stream is unusable if it fails.
+            codec
+                .flush_async()
+                .await
+                .map_err(|err| ReadError::Stream(StreamError::Io(err)))?;
+
+            while let Some(PendingRequest {
+                mut command,
+                sender,
+                id_sender,
+            }) = receiver.next().await
+            {
+                let request_id = {
+                    let mut pending_map = pending_map.lock().unwrap();
+                    pending_map.abandon_cancelled();
+
+                    match id_allocator.allocate(|id| pending_map.in_flight(id)) {
+                        Some(request_id) => {
+                            pending_map.senders.insert(request_id, sender);
+                            request_id
+                        }
+
+                        None => {
+                            sender.send(Err(WriteError::IdExhausted.into())).ok();
+                            continue;
+                        }
+                    }
+                };
+                id_sender.send(request_id).ok();
+
+                interceptors
+                    .lock()
+                    .unwrap()
+                    .before_write(request_id, &mut command);
+
+                let res = match codec.write_async(request_id, &command).await {
+                    Ok(_) => codec
+                        .flush_async()
+                        .await
+                        .map_err(|err| WriteError::Codec(StreamError::Io(err))),
+
+                    Err(err) => Err(err),
+                };
+
+                if let Err(err) = res {
+                    if let Some(sender) = pending_map.lock().unwrap().senders.remove(&request_id) {
+                        sender
+                            .send(Err(RequestError::from(err).with_request_id(request_id)))
+                            .ok();
+                    }
+                }
+            }
+
+            Ok::<_, ReadError>(())
+        };
+
+        let read_task = async {
+            let mut codec = BsonCommandCodec::from_parts(
+                read_half,
+                CodecBuffers {
+                    read_buf,
+                    write_buf: Vec::new(),
+                },
+            );
+
+            loop {
+                let mut read = match codec.read_async().await {
+                    Ok(read) => read,
+                    Err(err) => break Err::<(), _>(err),
+                };
+
+                if pending_map.lock().unwrap().discard_abandoned(read.id) {
+                    continue;
+                }
+
+                let mut interceptors = interceptors.lock().unwrap();
+                interceptors.after_read(&mut read);
+
+                let sender = pending_map.lock().unwrap().senders.remove(&read.id);
+                match sender {
+                    Some(sender) => {
+                        let ReadBsonCommand { id, mut command } = read;
+                        interceptors.on_response(id, &mut command);
+
+                        // Waiting side may be dropped already
+                        sender.send(Ok(ReadBsonCommand { id, command })).ok();
+                    }
+
+                    None => {
+                        broadcast_sender.unbounded_send(read).ok();
+                    }
+                }
+            }
+        };
+
+        pin_mut!(write_task, read_task);
+
+        // Keep reading broadcasts even if every handle is dropped
+        match future::select(read_task, write_task).await {
+            Either::Left((res, _)) => res,
+            Either::Right((Ok(_), read_task)) => read_task.await,
+            Either::Right((Err(err), _)) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bson::{doc, Document};
+    use futures::StreamExt;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    use crate::command::{codec::BsonCommandCodec, BsonCommand};
+
+    use super::SessionDriver;
+
+    #[tokio::test]
+    async fn discard_response_after_timeout() {
+        let (client, server) = tokio::io::duplex(4096);
+
+        let (driver, handle, mut broadcasts) = SessionDriver::new(client.compat());
+        tokio::spawn(driver.run());
+
+        let mut server = BsonCommandCodec::new(server.compat());
+        let command = BsonCommand::new_const("PING", 0, Document::new());
+
+        let err = handle
+            .request_with_timeout(&command, Some(Duration::from_millis(10)))
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+
+        let late_id = server.read_async().await.unwrap().id;
+        assert_eq!(err.request_id(), Some(late_id));
+
+        let (res, _) = futures::join!(handle.send_request(&command, None), async {
+            let read = server.read_async().await.unwrap();
+            assert_ne!(read.id, late_id);
+
+            server
+                .write_async(
+                    late_id,
+                    &BsonCommand::new_const("PING", 0, doc! { "late": true }),
+                )
+                .await
+                .unwrap();
+            server.write_async(read.id, &command).await.unwrap();
+            server
+                .write_async(100, &BsonCommand::new_const("MSG", 0, Document::new()))
+                .await
+                .unwrap();
+            server.flush_async().await.unwrap();
+        });
+
+        let response = res.unwrap();
+        assert_ne!(response.id, late_id);
+        assert_eq!(response.command.data, Document::new());
+
+        assert_eq!(broadcasts.next().await.unwrap().id, 100);
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bson::Document;
+use futures::{ready, stream::FusedStream, AsyncRead, AsyncWrite, Sink, Stream};
+use loco_protocol::command::codec::StreamError;
+use serde::Serialize;
+
+use super::{
+    codec::{decode_command, BsonCommandCodec, ReadError, WriteError},
+    BsonCommand, ReadBsonCommand,
+};
+
+/// [Stream] and [Sink] adapter of [BsonCommandCodec].
+///
+/// Stream ends if inner stream is closed on frame boundary.
+/// After inner stream fails, the error is returned once and stream ends.
+/// Sink buffers one command. [Sink::poll_ready] writes previous command before accepting next one.
+#[derive(Debug)]
+pub struct BsonCommandFramed<S> {
+    codec: BsonCommandCodec<S>,
+
+    /// True if inner stream is closed or failed
+    terminated: bool,
+}
+
+impl<S> BsonCommandFramed<S> {
+    pub fn new(stream: S) -> Self {
+        Self::from_codec(BsonCommandCodec::new(stream))
+    }
+
+    pub fn from_codec(codec: BsonCommandCodec<S>) -> Self {
+        Self {
+            codec,
+            terminated: false,
+        }
+    }
+
+    pub fn codec(&self) -> &BsonCommandCodec<S> {
+        &self.codec
+    }
+
+    pub fn codec_mut(&mut self) -> &mut BsonCommandCodec<S> {
+        &mut self.codec
+    }
+
+    pub fn into_inner(self) -> BsonCommandCodec<S> {
+        self.codec
+    }
+}
+
+impl<S: AsyncRead + Unpin> Stream for BsonCommandFramed<S> {
+    type Item = Result<ReadBsonCommand<Document>, ReadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.terminated {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.codec.poll_read_raw(cx)) {
+            Ok(command) => Poll::Ready(Some(decode_command(command))),
+
+            Err(ReadError::Stream(StreamError::Io(err))) => {
+                this.terminated = true;
+
+                if err.kind() == io::ErrorKind::UnexpectedEof && !this.codec.has_partial_frame() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Err(ReadError::Stream(StreamError::Io(err)))))
+                }
+            }
+
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> FusedStream for BsonCommandFramed<S> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<S: AsyncWrite + Unpin, T: Serialize> Sink<(i32, BsonCommand<T>)> for BsonCommandFramed<S> {
+    type Error = WriteError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut()
+            .codec
+            .poll_write_buffered(cx)
+            .map_err(io_write_error)
+    }
+
+    fn start_send(
+        self: Pin<&mut Self>,
+        (request_id, command): (i32, BsonCommand<T>),
+    ) -> Result<(), Self::Error> {
+        self.get_mut().codec.start_write(request_id, &command)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().codec.poll_flush(cx).map_err(io_write_error)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().codec.poll_close(cx).map_err(io_write_error)
+    }
+}
+
+fn io_write_error(err: io::Error) -> WriteError {
+    WriteError::Codec(StreamError::Io(err))
+}
@@ -0,0 +1,147 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::fmt::Debug;
+
+/// Source of request id candidates
+pub trait IdStrategy: Debug + Send {
+    /// Returns next id candidate without advancing
+    fn peek_id(&self) -> i32;
+
+    /// Returns next id candidate and advance
+    fn next_id(&mut self) -> i32;
+}
+
+/// Sequential ids from start.
+/// Wraps back to start after [i32::MAX].
+#[derive(Debug, Clone, Copy)]
+pub struct Sequential {
+    start: i32,
+    next: i32,
+}
+
+impl Sequential {
+    pub const fn new(start: i32) -> Self {
+        Self { start, next: start }
+    }
+}
+
+impl Default for Sequential {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl IdStrategy for Sequential {
+    fn peek_id(&self) -> i32 {
+        self.next
+    }
+
+    fn next_id(&mut self) -> i32 {
+        let id = self.next;
+
+        self.next = if id == i32::MAX { self.start } else { id + 1 };
+
+        id
+    }
+}
+
+/// Request id allocator skipping ids still in flight
+#[derive(Debug)]
+pub struct IdAllocator {
+    strategy: Box<dyn IdStrategy>,
+}
+
+impl IdAllocator {
+    /// Create allocator using [Sequential] ids from start
+    pub fn new(start: i32) -> Self {
+        Self::with_strategy(Sequential::new(start))
+    }
+
+    /// Create allocator using custom [IdStrategy]
+    pub fn with_strategy(strategy: impl IdStrategy + 'static) -> Self {
+        Self {
+            strategy: Box::new(strategy),
+        }
+    }
+
+    /// Next id candidate
+    pub fn peek(&self) -> i32 {
+        self.strategy.peek_id()
+    }
+
+    /// Allocate next id which in_flight returns false.
+    /// Returns None if strategy gives first candidate again, which means every id it gives is in flight.
+    pub fn allocate(&mut self, in_flight: impl Fn(i32) -> bool) -> Option<i32> {
+        let first = self.strategy.next_id();
+        let mut id = first;
+
+        while in_flight(id) {
+            id = self.strategy.next_id();
+
+            if id == first {
+                return None;
+            }
+        }
+
+        Some(id)
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdAllocator, IdStrategy, Sequential};
+
+    #[test]
+    fn sequential_wraps_to_start() {
+        let mut strategy = Sequential::new(i32::MAX - 1);
+
+        assert_eq!(strategy.next_id(), i32::MAX - 1);
+        assert_eq!(strategy.next_id(), i32::MAX);
+        assert_eq!(strategy.next_id(), i32::MAX - 1);
+    }
+
+    #[test]
+    fn allocate_from_start_id() {
+        let mut allocator = IdAllocator::new(42);
+
+        assert_eq!(allocator.peek(), 42);
+        assert_eq!(allocator.allocate(|_| false), Some(42));
+        assert_eq!(allocator.allocate(|_| false), Some(43));
+    }
+
+    #[test]
+    fn allocate_skips_in_flight() {
+        let mut allocator = IdAllocator::new(0);
+
+        assert_eq!(allocator.allocate(|id| id < 3), Some(3));
+        assert_eq!(allocator.allocate(|id| id == 4), Some(5));
+    }
+
+    #[test]
+    fn allocate_wraps_around_in_flight() {
+        let mut allocator = IdAllocator::new(i32::MAX - 2);
+
+        assert_eq!(allocator.allocate(|_| false), Some(i32::MAX - 2));
+        assert_eq!(
+            allocator.allocate(|id| id >= i32::MAX - 1),
+            Some(i32::MAX - 2)
+        );
+    }
+
+    #[test]
+    fn allocate_fails_if_every_id_in_flight() {
+        let mut allocator = IdAllocator::new(i32::MAX - 2);
+
+        assert_eq!(allocator.allocate(|_| true), None);
+    }
+}
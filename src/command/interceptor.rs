@@ -0,0 +1,80 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::fmt::Debug;
+
+use bson::Document;
+
+use super::{BsonCommand, ReadBsonCommand};
+
+/// Hooks called by [super::session::BsonCommandSession].
+/// Commands can be inspected or rewritten in place.
+pub trait Interceptor: Send {
+    /// Called before writing request command
+    fn before_write(&mut self, _request_id: i32, _command: &mut BsonCommand<Document>) {}
+
+    /// Called after reading any command
+    fn after_read(&mut self, _read: &mut ReadBsonCommand<Document>) {}
+
+    /// Called when response command is matched with its request
+    fn on_response(&mut self, _request_id: i32, _command: &mut BsonCommand<Document>) {}
+}
+
+/// Ordered list of [Interceptor].
+/// Hooks are called in registration order.
+#[derive(Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Box<dyn Interceptor>>,
+}
+
+impl InterceptorChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append [Interceptor] to end of chain
+    pub fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(Box::new(interceptor));
+    }
+
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.interceptors.clear();
+    }
+
+    pub fn before_write(&mut self, request_id: i32, command: &mut BsonCommand<Document>) {
+        for interceptor in &mut self.interceptors {
+            interceptor.before_write(request_id, command);
+        }
+    }
+
+    pub fn after_read(&mut self, read: &mut ReadBsonCommand<Document>) {
+        for interceptor in &mut self.interceptors {
+            interceptor.after_read(read);
+        }
+    }
+
+    pub fn on_response(&mut self, request_id: i32, command: &mut BsonCommand<Document>) {
+        for interceptor in &mut self.interceptors {
+            interceptor.on_response(request_id, command);
+        }
+    }
+}
+
+impl Debug for InterceptorChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
@@ -6,6 +6,15 @@
 
 pub mod codec;
 pub mod session;
+pub mod driver;
+pub mod record;
+pub mod interceptor;
+pub mod split;
+pub mod id;
+pub mod framed;
+
+#[cfg(feature = "tokio")]
+pub mod tokio;
 
 use std::borrow::Cow;
 
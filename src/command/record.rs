@@ -0,0 +1,441 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, Cursor, Read, Write},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bson::{spec::BinarySubtype, Binary, Document};
+use futures::{ready, AsyncRead, AsyncWrite};
+use serde::{Deserialize, Serialize};
+
+use crate::time::now_millis;
+
+use super::{
+    codec::{parse_frame, take_raw_frame, BsonCommandCodec, ReadError},
+    ReadBsonCommand,
+};
+
+/// Direction of recorded command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Read,
+    Write,
+}
+
+/// Recorded command frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordEntry {
+    #[serde(rename = "dir")]
+    pub direction: Direction,
+
+    /// Record time in unix millis
+    #[serde(rename = "time")]
+    pub timestamp: i64,
+
+    pub id: i32,
+
+    /// Status of frame header
+    pub status: i16,
+
+    /// Raw frame including header.
+    /// Frames with non zero status or invalid bson data are kept as is.
+    pub frame: Binary,
+}
+
+impl RecordEntry {
+    /// Create entry from complete raw frame
+    pub fn new(direction: Direction, frame: Vec<u8>) -> Result<Self, RecordError> {
+        let header = parse_frame(&frame)?.header;
+
+        Ok(Self {
+            direction,
+            timestamp: now_millis(),
+            id: header.id,
+            status: header.status,
+            frame: Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: frame,
+            },
+        })
+    }
+
+    /// Decode recorded frame
+    pub fn decode(&self) -> Result<ReadBsonCommand<Document>, ReadError> {
+        BsonCommandCodec::new(self.frame.bytes.as_slice()).read()
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    Encode(bson::ser::Error),
+    Decode(bson::de::Error),
+    Read(ReadError),
+}
+
+impl From<io::Error> for RecordError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<bson::ser::Error> for RecordError {
+    fn from(err: bson::ser::Error) -> Self {
+        Self::Encode(err)
+    }
+}
+
+impl From<bson::de::Error> for RecordError {
+    fn from(err: bson::de::Error) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl From<ReadError> for RecordError {
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::Io(err) => err.fmt(f),
+            RecordError::Encode(err) => err.fmt(f),
+            RecordError::Decode(err) => err.fmt(f),
+            RecordError::Read(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Append [RecordEntry] as bson document
+pub fn write_entry(writer: &mut impl Write, entry: &RecordEntry) -> Result<(), RecordError> {
+    bson::to_document(entry)?.to_writer(writer)?;
+
+    Ok(())
+}
+
+/// Read every [RecordEntry] of recording
+pub fn read_entries(mut reader: impl Read) -> Result<Vec<RecordEntry>, RecordError> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+
+    let mut cursor = Cursor::new(buf);
+    let mut entries = Vec::new();
+
+    while (cursor.position() as usize) < cursor.get_ref().len() {
+        let doc = Document::from_reader(&mut cursor)?;
+        entries.push(bson::from_document(doc)?);
+    }
+
+    Ok(entries)
+}
+
+/// Stream wrapper appending every command frame read and written through it to writer.
+/// Frames are split from passing bytes so it can be used under any codec, session, driver or client.
+/// Recording failure never fails the stream. First failure can be taken using [RecordingStream::take_error].
+#[derive(Debug)]
+pub struct RecordingStream<S, W> {
+    stream: S,
+    writer: W,
+
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
+
+    error: Option<RecordError>,
+}
+
+impl<S, W: Write> RecordingStream<S, W> {
+    pub fn new(stream: S, writer: W) -> Self {
+        Self {
+            stream,
+            writer,
+
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+
+            error: None,
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    pub fn into_inner(self) -> (S, W) {
+        (self.stream, self.writer)
+    }
+
+    /// Take first recording failure
+    pub fn take_error(&mut self) -> Option<RecordError> {
+        self.error.take()
+    }
+
+    /// Record every complete frame of passed data
+    fn record(&mut self, direction: Direction, data: &[u8]) {
+        let buf = match direction {
+            Direction::Read => &mut self.read_buf,
+            Direction::Write => &mut self.write_buf,
+        };
+        buf.extend_from_slice(data);
+
+        while let Some(frame) = take_raw_frame(buf) {
+            let res = RecordEntry::new(direction, frame)
+                .and_then(|entry| write_entry(&mut self.writer, &entry));
+
+            if let Err(err) = res {
+                self.error.get_or_insert(err);
+            }
+        }
+    }
+
+    fn flush_writer(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            self.error.get_or_insert(err.into());
+        }
+    }
+}
+
+impl<S: Read, W: Write> Read for RecordingStream<S, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.stream.read(buf)?;
+        self.record(Direction::Read, &buf[..read]);
+
+        Ok(read)
+    }
+}
+
+impl<S: Write, W: Write> Write for RecordingStream<S, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.stream.write(buf)?;
+        self.record(Direction::Write, &buf[..written]);
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()?;
+        self.flush_writer();
+
+        Ok(())
+    }
+}
+
+impl<S: AsyncRead + Unpin, W: Write + Unpin> AsyncRead for RecordingStream<S, W> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        let read = ready!(Pin::new(&mut this.stream).poll_read(cx, buf))?;
+        this.record(Direction::Read, &buf[..read]);
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl<S: AsyncWrite + Unpin, W: Write + Unpin> AsyncWrite for RecordingStream<S, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        let written = ready!(Pin::new(&mut this.stream).poll_write(cx, buf))?;
+        this.record(Direction::Write, &buf[..written]);
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        ready!(Pin::new(&mut this.stream).poll_flush(cx))?;
+        this.flush_writer();
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        ready!(Pin::new(&mut this.stream).poll_close(cx))?;
+        this.flush_writer();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Stream replaying raw frames of recorded read commands.
+/// Written data are stored and can be inspected using [ReplayStream::written].
+#[derive(Debug)]
+pub struct ReplayStream {
+    data: Cursor<Vec<u8>>,
+    written: Vec<u8>,
+}
+
+impl ReplayStream {
+    /// Create stream from [Direction::Read] entries
+    pub fn new(entries: impl IntoIterator<Item = RecordEntry>) -> Self {
+        let mut data = Vec::new();
+
+        for entry in entries {
+            if entry.direction == Direction::Read {
+                data.extend_from_slice(&entry.frame.bytes);
+            }
+        }
+
+        Self {
+            data: Cursor::new(data),
+            written: Vec::new(),
+        }
+    }
+
+    /// Create stream from recording
+    pub fn from_reader(reader: impl Read) -> Result<Self, RecordError> {
+        Ok(Self::new(read_entries(reader)?))
+    }
+
+    /// Data written to this stream
+    pub fn written(&self) -> &[u8] {
+        &self.written
+    }
+}
+
+impl Read for ReplayStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.data.read(buf)
+    }
+}
+
+impl Write for ReplayStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsyncRead for ReplayStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().data.read(buf))
+    }
+}
+
+impl AsyncWrite for ReplayStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Write::write(self.get_mut(), buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use bson::{doc, Document};
+    use loco_protocol::command::{builder::CommandBuilder, codec::CommandCodec};
+
+    use crate::command::{
+        codec::{BsonCommandCodec, ReadError},
+        BsonCommand,
+    };
+
+    use super::{read_entries, Direction, RecordingStream, ReplayStream};
+
+    #[test]
+    fn record_replay_round_trip() {
+        let data = doc! { "chatId": 1_i64 };
+
+        let mut server = BsonCommandCodec::new(Vec::new());
+        server
+            .write(1, &BsonCommand::new_const("MSG", 0, data.clone()))
+            .unwrap();
+        let mut incoming = server.into_inner();
+
+        let mut corrupted = CommandBuilder::new(2, "MSG").build(0, vec![1, 2, 3]);
+        corrupted.header.status = -300;
+        CommandCodec::new(&mut incoming).write(&corrupted).unwrap();
+
+        let mut codec =
+            BsonCommandCodec::new(RecordingStream::new(Cursor::new(incoming), Vec::new()));
+
+        assert_eq!(codec.read().unwrap().id, 1);
+        assert!(matches!(codec.read(), Err(ReadError::Corrupted(_))));
+
+        codec
+            .write(3, &BsonCommand::new_const("PING", 0, Document::new()))
+            .unwrap();
+        codec.flush().unwrap();
+
+        let mut stream = codec.into_inner();
+        assert!(stream.take_error().is_none());
+
+        let (_, recording) = stream.into_inner();
+        let entries = read_entries(recording.as_slice()).unwrap();
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.direction, entry.id, entry.status))
+                .collect::<Vec<_>>(),
+            [
+                (Direction::Read, 1, 0),
+                (Direction::Read, 2, -300),
+                (Direction::Write, 3, 0)
+            ]
+        );
+
+        let mut codec = BsonCommandCodec::new(ReplayStream::new(entries));
+
+        let read = codec.read().unwrap();
+        assert_eq!(read.id, 1);
+        assert_eq!(read.command.data, data);
+
+        match codec.read() {
+            Err(ReadError::Corrupted(command)) => {
+                assert_eq!(command.header.id, 2);
+                assert_eq!(command.header.status, -300);
+                assert_eq!(command.data, [1, 2, 3]);
+            }
+
+            res => panic!("expected corrupted frame. got: {:?}", res),
+        }
+    }
+}
@@ -5,45 +5,293 @@
  */
 
 use std::{
+    borrow::Cow,
+    collections::HashSet,
+    error::Error,
+    fmt::Display,
     io::{Read, Write},
+    mem,
+    time::Duration,
 };
 
-use bson::Document;
-use futures::{AsyncRead, AsyncWrite};
+use bson::{Bson, Document};
+use futures::{
+    future::{self, Either},
+    pin_mut, AsyncRead, AsyncWrite, Future,
+};
+use futures_timer::Delay;
 use indexmap::IndexMap;
 use loco_protocol::command::codec::StreamError;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 
 use super::{
-    codec::{BsonCommandCodec, ReadError, WriteError},
+    codec::{
+        decode_command, deserialize_command, BsonCommandCodec, CodecBuffers, ReadError, WriteError,
+    },
+    id::IdAllocator,
+    interceptor::{Interceptor, InterceptorChain},
     BsonCommand, ReadBsonCommand,
 };
 
+/// Kind of [RequestError]
 #[derive(Debug)]
-pub enum RequestError {
+pub enum RequestErrorKind {
     Write(WriteError),
     Read(ReadError),
+
+    /// Response data does not match expected type
+    Deserialize(bson::de::Error),
+
+    /// Session driver is stopped before receiving response
+    Closed,
+
+    /// Response did not arrive in time
+    Timeout,
+}
+
+impl RequestErrorKind {
+    /// Short description without inner error
+    fn label(&self) -> &'static str {
+        match self {
+            RequestErrorKind::Write(_) => "Request write failed",
+            RequestErrorKind::Read(_) => "Response read failed",
+            RequestErrorKind::Deserialize(_) => "Response deserialize failed",
+            RequestErrorKind::Closed => "Session closed",
+            RequestErrorKind::Timeout => "Request timed out",
+        }
+    }
+}
+
+impl Display for RequestErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestErrorKind::Write(err) => err.fmt(f),
+            RequestErrorKind::Read(err) => err.fmt(f),
+            RequestErrorKind::Deserialize(err) => err.fmt(f),
+            RequestErrorKind::Closed => write!(f, "Session closed"),
+            RequestErrorKind::Timeout => write!(f, "Request timed out"),
+        }
+    }
+}
+
+/// Request error with context of failed request
+#[derive(Debug)]
+pub struct RequestError {
+    kind: RequestErrorKind,
+
+    method: Option<Cow<'static, str>>,
+    request_id: Option<i32>,
+    status: Option<i16>,
+
+    data: Option<Document>,
+}
+
+impl RequestError {
+    pub fn new(kind: RequestErrorKind) -> Self {
+        Self {
+            kind,
+            method: None,
+            request_id: None,
+            status: None,
+            data: None,
+        }
+    }
+
+    pub fn closed() -> Self {
+        Self::new(RequestErrorKind::Closed)
+    }
+
+    pub fn timeout() -> Self {
+        Self::new(RequestErrorKind::Timeout)
+    }
+
+    pub fn kind(&self) -> &RequestErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> RequestErrorKind {
+        self.kind
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self.kind, RequestErrorKind::Closed)
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self.kind, RequestErrorKind::Timeout)
+    }
+
+    /// Method of failed request
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// Id of failed request
+    pub fn request_id(&self) -> Option<i32> {
+        self.request_id
+    }
+
+    /// Status of response
+    pub fn status(&self) -> Option<i16> {
+        self.status
+    }
+
+    /// Raw response data which failed to deserialize
+    pub fn data(&self) -> Option<&Document> {
+        self.data.as_ref()
+    }
+
+    pub fn with_method(mut self, method: impl Into<Cow<'static, str>>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: i32) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    /// Attach raw response data. Status is read from data if exists.
+    pub fn with_data(mut self, data: Document) -> Self {
+        if let Some(status) = data.get("status").and_then(Bson::as_i32) {
+            self.status = Some(status as i16);
+        }
+
+        self.data = Some(data);
+        self
+    }
+}
+
+impl From<RequestErrorKind> for RequestError {
+    fn from(kind: RequestErrorKind) -> Self {
+        Self::new(kind)
+    }
 }
 
 impl From<WriteError> for RequestError {
     fn from(err: WriteError) -> Self {
-        Self::Write(err)
+        Self::new(RequestErrorKind::Write(err))
     }
 }
 
 impl From<ReadError> for RequestError {
     fn from(err: ReadError) -> Self {
-        Self::Read(err)
+        match err {
+            ReadError::Corrupted(command) => {
+                let status = command.header.status;
+
+                let mut err = Self::new(RequestErrorKind::Read(ReadError::Corrupted(command)));
+                err.status = Some(status);
+                err
+            }
+
+            ReadError::Deserialize { error, data } => {
+                Self::new(RequestErrorKind::Deserialize(error)).with_data(data)
+            }
+
+            ReadError::Timeout => Self::timeout(),
+
+            err => Self::new(RequestErrorKind::Read(err)),
+        }
+    }
+}
+
+impl From<bson::de::Error> for RequestError {
+    fn from(err: bson::de::Error) -> Self {
+        Self::new(RequestErrorKind::Deserialize(err))
+    }
+}
+
+/// Inner error is not printed. It is returned by [Error::source].
+impl Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind.label())?;
+
+        let mut separator = ".";
+
+        if let Some(method) = &self.method {
+            write!(f, "{} method: {}", separator, method)?;
+            separator = ",";
+        }
+
+        if let Some(request_id) = self.request_id {
+            write!(f, "{} id: {}", separator, request_id)?;
+            separator = ",";
+        }
+
+        if let Some(status) = self.status {
+            write!(f, "{} status: {}", separator, status)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for RequestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            RequestErrorKind::Write(err) => Some(err),
+            RequestErrorKind::Read(err) => Some(err),
+            RequestErrorKind::Deserialize(err) => Some(err),
+            RequestErrorKind::Closed | RequestErrorKind::Timeout => None,
+        }
     }
 }
 
+/// Policy applied when unsolicited command buffer of [BsonCommandSession] is full.
+/// Responses of pending requests are always buffered even over capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop oldest buffered command which is not response of pending request to make room.
+    /// Drops command read and fails with [ReadError::BufferFull] if every buffered command is pending response.
+    #[default]
+    DropOldest,
+
+    /// Drop unsolicited command read and fail with [ReadError::BufferFull]
+    Error,
+
+    /// Stop reading stream and fail with [ReadError::BufferFull].
+    /// Nothing is dropped. Drain buffer using read methods and retry.
+    Backpressure,
+}
+
+/// Counters of unsolicited command buffer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferStats {
+    /// Total count of buffered commands
+    pub buffered: u64,
+
+    /// Total count of dropped commands
+    pub dropped: u64,
+
+    /// Total count of discarded late responses of abandoned requests
+    pub discarded: u64,
+}
+
 /// Async Command session.
 /// Provide methods for requesting command response and broadcast command handling.
 /// Useful when creating client.
 #[derive(Debug)]
 pub struct BsonCommandSession<S> {
-    current_id: i32,
+    id_allocator: IdAllocator,
+
     read_map: IndexMap<i32, BsonCommand<Document>>,
+    buffer_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    buffer_stats: BufferStats,
+
+    /// Requests whose responses are not returned yet
+    outstanding: HashSet<i32>,
+
+    /// Requests whose responses are discarded on arrival
+    abandoned: HashSet<i32>,
+
+    /// Requests used by running call. Abandoned if the call is cancelled or fails.
+    in_progress: Vec<i32>,
+
+    timeout: Option<Duration>,
+
+    interceptors: InterceptorChain,
 
     codec: BsonCommandCodec<S>,
 }
@@ -52,34 +300,356 @@ impl<S> BsonCommandSession<S> {
     /// Create new [BsonCommandSession]
     pub fn new(stream: S) -> Self {
         Self {
-            current_id: 0,
+            id_allocator: IdAllocator::default(),
+
             read_map: IndexMap::new(),
+            buffer_capacity: None,
+            overflow_policy: OverflowPolicy::default(),
+            buffer_stats: BufferStats::default(),
+
+            outstanding: HashSet::new(),
+            abandoned: HashSet::new(),
+            in_progress: Vec::new(),
+
+            timeout: None,
+
+            interceptors: InterceptorChain::new(),
 
             codec: BsonCommandCodec::new(stream),
         }
     }
 
+    /// Next request id candidate
     pub fn current_id(&self) -> i32 {
-        self.current_id
+        self.id_allocator.peek()
+    }
+
+    pub fn id_allocator(&self) -> &IdAllocator {
+        &self.id_allocator
+    }
+
+    /// Replace [IdAllocator]. Use it to start from given id or use custom strategy.
+    pub fn set_id_allocator(&mut self, id_allocator: IdAllocator) {
+        self.id_allocator = id_allocator;
+    }
+
+    /// Allocate request id not colliding with outstanding requests, buffered or abandoned responses
+    fn allocate_id(&mut self) -> Result<i32, WriteError> {
+        let (read_map, outstanding, abandoned) =
+            (&self.read_map, &self.outstanding, &self.abandoned);
+
+        self.id_allocator
+            .allocate(|id| {
+                outstanding.contains(&id) || read_map.contains_key(&id) || abandoned.contains(&id)
+            })
+            .ok_or(WriteError::IdExhausted)
+    }
+
+    /// Give up response of request.
+    /// Buffered response is dropped and late response is discarded on arrival.
+    pub fn abandon(&mut self, id: i32) {
+        self.outstanding.remove(&id);
+
+        if self.read_map.shift_remove(&id).is_some() {
+            self.buffer_stats.discarded += 1;
+        } else {
+            self.abandoned.insert(id);
+        }
+    }
+
+    /// Abandon requests left by cancelled or failed call
+    fn abandon_cancelled(&mut self) {
+        for id in mem::take(&mut self.in_progress) {
+            self.abandon(id);
+        }
+    }
+
+    /// Returns true if id is abandoned and its response should be discarded
+    fn discard_abandoned(&mut self, id: i32) -> bool {
+        if self.abandoned.remove(&id) {
+            self.buffer_stats.discarded += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Timeout of async response methods. None if they wait forever.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set timeout of async response methods.
+    /// Timed out call fails with [ReadError::Timeout] and its requests are abandoned.
+    /// Use [crate::structs::connection::ConnectionData::request_timeout_duration] for server provided value.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Finish write call. Requests written are abandoned if the call failed.
+    fn finish_write<T, E>(&mut self, res: &Result<T, E>) {
+        if res.is_err() {
+            self.abandon_cancelled();
+        } else {
+            self.in_progress.clear();
+        }
+    }
+
+    /// Finish async read call. Requests left are abandoned if the call timed out.
+    fn finish_in_progress<T>(&mut self, res: &Result<T, ReadError>) {
+        if matches!(res, Err(ReadError::Timeout)) {
+            self.abandon_cancelled();
+        } else {
+            self.in_progress.clear();
+        }
+    }
+
+    /// Capacity of unsolicited command buffer. None if unbounded.
+    pub fn buffer_capacity(&self) -> Option<usize> {
+        self.buffer_capacity
+    }
+
+    /// Set capacity of buffer storing commands read while waiting for response.
+    /// Excess commands are handled following [OverflowPolicy].
+    pub fn set_buffer_capacity(&mut self, capacity: Option<usize>) {
+        self.buffer_capacity = capacity;
+    }
+
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// Count of currently buffered commands
+    pub fn buffered_len(&self) -> usize {
+        self.read_map.len()
+    }
+
+    pub fn buffer_stats(&self) -> BufferStats {
+        self.buffer_stats
+    }
+
+    fn buffer_full(&self) -> bool {
+        matches!(self.buffer_capacity, Some(capacity) if self.read_map.len() >= capacity)
+    }
+
+    /// Fails if buffer is full and policy is [OverflowPolicy::Backpressure]
+    fn check_backpressure(&self) -> Result<(), ReadError> {
+        if self.overflow_policy == OverflowPolicy::Backpressure && self.buffer_full() {
+            Err(ReadError::BufferFull)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Store command read while waiting for other response.
+    /// Overflow policy applies only to commands which are not response of pending request.
+    fn buffer_command(&mut self, id: i32, command: BsonCommand<Document>) -> Result<(), ReadError> {
+        if self.buffer_full() && !self.outstanding.contains(&id) {
+            match self.overflow_policy {
+                OverflowPolicy::DropOldest => {
+                    let outstanding = &self.outstanding;
+                    let oldest = self
+                        .read_map
+                        .keys()
+                        .position(|buffered_id| !outstanding.contains(buffered_id));
+
+                    match oldest {
+                        Some(index) => {
+                            self.read_map.shift_remove_index(index);
+                            self.buffer_stats.dropped += 1;
+                        }
+
+                        // Every buffered command is pending response
+                        None => {
+                            self.buffer_stats.dropped += 1;
+                            return Err(ReadError::BufferFull);
+                        }
+                    }
+                }
+
+                OverflowPolicy::Error => {
+                    self.buffer_stats.dropped += 1;
+                    return Err(ReadError::BufferFull);
+                }
+
+                // Checked before reading. Buffer can be over capacity only if capacity is lowered.
+                OverflowPolicy::Backpressure => {}
+            }
+        }
+
+        self.read_map.insert(id, command);
+        self.buffer_stats.buffered += 1;
+
+        Ok(())
+    }
+
+    /// Append [Interceptor] to end of interceptor chain
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub fn interceptors(&self) -> &InterceptorChain {
+        &self.interceptors
+    }
+
+    pub fn interceptors_mut(&mut self) -> &mut InterceptorChain {
+        &mut self.interceptors
     }
 
-    /// Consume self and returns inner stream
+    /// Convert command for interceptors.
+    /// Returns None if there are no interceptors so command can be written directly.
+    fn intercept_write(
+        &mut self,
+        request_id: i32,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<Option<BsonCommand<Document>>, WriteError> {
+        if self.interceptors.is_empty() {
+            return Ok(None);
+        }
+
+        let mut command = BsonCommand {
+            method: command.method.clone(),
+            data_type: command.data_type,
+            data: bson::to_document(&command.data)?,
+        };
+        self.interceptors.before_write(request_id, &mut command);
+
+        Ok(Some(command))
+    }
+
+    /// Consume self and returns inner stream.
+    /// Buffered commands, partially read frame and unwritten data are dropped.
+    /// Use [BsonCommandSession::try_into_inner] to keep them.
     pub fn into_inner(self) -> S {
         self.codec.into_inner()
     }
+
+    /// Consume self and returns inner stream.
+    /// Fails returning self if there are buffered commands, partially read frame or unwritten data.
+    pub fn try_into_inner(self) -> Result<S, Self> {
+        let (stream, buffers) = self.codec.into_parts();
+
+        if self.read_map.is_empty() && buffers.is_empty() {
+            Ok(stream)
+        } else {
+            Err(Self {
+                codec: BsonCommandCodec::from_parts(stream, buffers),
+                ..self
+            })
+        }
+    }
+
+    pub(crate) fn into_parts(self) -> SessionParts<S> {
+        let (stream, buffers) = self.codec.into_parts();
+
+        SessionParts {
+            id_allocator: self.id_allocator,
+            read_map: self.read_map,
+            interceptors: self.interceptors,
+            timeout: self.timeout,
+            stream,
+            buffers,
+        }
+    }
+
+    /// Take back request abandoned by cancelled call so its response can be waited again.
+    /// Fails with [ReadError::NotOutstanding] if its response is already returned or discarded.
+    fn reclaim(&mut self, id: i32) -> Result<(), ReadError> {
+        self.in_progress.retain(|in_progress| *in_progress != id);
+
+        if self.abandoned.remove(&id) {
+            self.outstanding.insert(id);
+        }
+
+        if self.outstanding.contains(&id) || self.read_map.contains_key(&id) {
+            Ok(())
+        } else {
+            Err(ReadError::NotOutstanding)
+        }
+    }
+}
+
+/// Parts of [BsonCommandSession] passed on conversion
+#[derive(Debug)]
+pub(crate) struct SessionParts<S> {
+    pub id_allocator: IdAllocator,
+    pub read_map: IndexMap<i32, BsonCommand<Document>>,
+    pub interceptors: InterceptorChain,
+    pub timeout: Option<Duration>,
+
+    pub stream: S,
+    pub buffers: CodecBuffers,
 }
 
 impl<S: Write> BsonCommandSession<S> {
     /// Send and create response ticket of this request.
     /// The response is guaranteed to have same id of request command.
+    /// If it fails, the request is abandoned.
     pub fn request(&mut self, command: &BsonCommand<impl Serialize>) -> Result<i32, WriteError> {
-        let request_id = self.current_id;
-        self.current_id += 1;
+        self.request_with_id(command).map_err(|(_, err)| err)
+    }
+
+    /// Send request. Error has id of failed request if it is allocated.
+    pub(crate) fn request_with_id(
+        &mut self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<i32, (Option<i32>, WriteError)> {
+        self.abandon_cancelled();
+
+        let res = self
+            .write_request(command)
+            .and_then(|request_id| {
+                self.codec
+                    .flush()
+                    .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+                Ok(request_id)
+            })
+            // Request left in progress is the failed one
+            .map_err(|err| (self.in_progress.last().copied(), err));
+        self.finish_write(&res);
 
-        self.codec.write(request_id, command)?;
-        self.codec
-            .flush()
-            .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+        res
+    }
+
+    /// Send every commands flushing once.
+    /// Returns response tickets in same order of commands.
+    /// If it fails, every request written is abandoned.
+    pub fn request_batch<'a, T: Serialize + 'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a BsonCommand<T>>,
+    ) -> Result<Vec<i32>, WriteError> {
+        self.abandon_cancelled();
+
+        let res = commands
+            .into_iter()
+            .map(|command| self.write_request(command))
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|ids| {
+                self.codec
+                    .flush()
+                    .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+                Ok(ids)
+            });
+        self.finish_write(&res);
+
+        res
+    }
+
+    fn write_request(&mut self, command: &BsonCommand<impl Serialize>) -> Result<i32, WriteError> {
+        let request_id = self.allocate_id()?;
+        self.outstanding.insert(request_id);
+        self.in_progress.push(request_id);
+
+        match self.intercept_write(request_id, command)? {
+            Some(command) => self.codec.write(request_id, &command)?,
+            None => self.codec.write(request_id, command)?,
+        }
 
         Ok(request_id)
     }
@@ -88,18 +658,81 @@ impl<S: Write> BsonCommandSession<S> {
 impl<S: AsyncWrite + Unpin> BsonCommandSession<S> {
     /// Send and create response ticket of this request asynchronously.
     /// The response is guaranteed to have same id returned.
+    /// If cancelled or failed, the request is abandoned.
     pub async fn request_async(
         &mut self,
         command: &BsonCommand<impl Serialize>,
     ) -> Result<i32, WriteError> {
-        let request_id = self.current_id;
-        self.current_id += 1;
-
-        self.codec.write_async(request_id, command).await?;
-        self.codec
-            .flush_async()
+        self.request_with_id_async(command)
             .await
-            .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+            .map_err(|(_, err)| err)
+    }
+
+    /// Send request asynchronously. Error has id of failed request if it is allocated.
+    pub(crate) async fn request_with_id_async(
+        &mut self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<i32, (Option<i32>, WriteError)> {
+        self.abandon_cancelled();
+
+        let res = async {
+            let request_id = self.write_request_async(command).await?;
+            self.codec
+                .flush_async()
+                .await
+                .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+            Ok(request_id)
+        }
+        .await;
+
+        // Request left in progress is the failed one
+        let res = res.map_err(|err| (self.in_progress.last().copied(), err));
+        self.finish_write(&res);
+
+        res
+    }
+
+    /// Send every commands asynchronously flushing once.
+    /// Returns response tickets in same order of commands.
+    /// If cancelled or failed, every request written is abandoned.
+    pub async fn request_batch_async<'a, T: Serialize + 'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a BsonCommand<T>>,
+    ) -> Result<Vec<i32>, WriteError> {
+        self.abandon_cancelled();
+
+        let res = async {
+            let mut ids = Vec::new();
+            for command in commands {
+                ids.push(self.write_request_async(command).await?);
+            }
+
+            self.codec
+                .flush_async()
+                .await
+                .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+            Ok(ids)
+        }
+        .await;
+        self.finish_write(&res);
+
+        res
+    }
+
+    async fn write_request_async(
+        &mut self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<i32, WriteError> {
+        let request_id = self.allocate_id()?;
+        self.outstanding.insert(request_id);
+        self.in_progress.push(request_id);
+
+        match self.intercept_write(request_id, command)? {
+            Some(command) => self.codec.write_async(request_id, &command).await?,
+            None => self.codec.write_async(request_id, command).await?,
+        }
 
         Ok(request_id)
     }
@@ -109,62 +742,429 @@ impl<S: Read> BsonCommandSession<S> {
     /// Read next [BsonCommand]
     pub fn read(&mut self) -> Result<ReadBsonCommand<Document>, ReadError> {
         if let Some(next_id) = self.read_map.keys().next().copied() {
-            Ok(ReadBsonCommand {
+            self.outstanding.remove(&next_id);
+
+            return Ok(ReadBsonCommand {
                 id: next_id,
-                command: self.read_map.shift_remove(&next_id).unwrap()
-            })
-        } else {
-            let read = self.codec.read()?;
-            Ok(read)
+                command: self.read_map.shift_remove(&next_id).unwrap(),
+            });
+        }
+
+        loop {
+            let mut read = self.codec.read()?;
+            if self.discard_abandoned(read.id) {
+                continue;
+            }
+
+            self.interceptors.after_read(&mut read);
+            self.outstanding.remove(&read.id);
+
+            return Ok(read);
         }
     }
 
     /// Read [BsonCommand] response
     pub fn response(&mut self, id: i32) -> Result<BsonCommand<Document>, ReadError> {
-        if let Some(read) = self.read_map.shift_remove(&id) {
+        self.reclaim(id)?;
+
+        if let Some(mut read) = self.read_map.shift_remove(&id) {
+            self.outstanding.remove(&id);
+            self.interceptors.on_response(id, &mut read);
             return Ok(read);
         }
 
         loop {
-            let ReadBsonCommand { id: request_id, command } = self.codec.read()?;
+            self.check_backpressure()?;
+
+            let mut read = self.codec.read()?;
+            if self.discard_abandoned(read.id) {
+                continue;
+            }
+
+            self.interceptors.after_read(&mut read);
+
+            let ReadBsonCommand { id: request_id, mut command } = read;
 
             if request_id == id {
+                self.outstanding.remove(&id);
+                self.interceptors.on_response(id, &mut command);
                 return Ok(command);
             } else {
-                self.read_map.insert(request_id, command);
+                self.buffer_command(request_id, command)?;
+            }
+        }
+    }
+
+    /// Read responses of every tickets.
+    /// Returns responses in same order of ids regardless of arrival order.
+    pub fn responses(&mut self, ids: &[i32]) -> Result<Vec<BsonCommand<Document>>, ReadError> {
+        ids.iter().map(|id| self.response(*id)).collect()
+    }
+
+    /// Read [BsonCommand] response deserializing data directly from frame.
+    /// Other commands are buffered as [Document].
+    pub fn response_typed<D: DeserializeOwned>(
+        &mut self,
+        id: i32,
+    ) -> Result<BsonCommand<D>, ReadError> {
+        self.reclaim(id)?;
+
+        if !self.interceptors.is_empty() || self.read_map.contains_key(&id) {
+            return deserialize_command(self.response(id)?);
+        }
+
+        loop {
+            self.check_backpressure()?;
+
+            let command = self.codec.read_raw()?;
+            if self.discard_abandoned(command.header.id) {
+                continue;
+            }
+
+            if command.header.id == id {
+                self.outstanding.remove(&id);
+                return Ok(decode_command(command)?.command);
+            } else {
+                let ReadBsonCommand { id: request_id, command } = decode_command(command)?;
+                self.buffer_command(request_id, command)?;
             }
         }
     }
 }
 
+/// Async read methods are cancellation safe.
+/// Requests waited by cancelled or timed out call are abandoned so their late responses are discarded,
+/// unless next call waits for them again.
+/// Waiting again after the response is discarded fails with [ReadError::NotOutstanding].
 impl<S: AsyncRead + Unpin> BsonCommandSession<S> {
     /// Read next [BsonCommand] asynchronously
     pub async fn read_async(&mut self) -> Result<ReadBsonCommand<Document>, ReadError> {
+        self.abandon_cancelled();
+
         if let Some(next_id) = self.read_map.keys().next().copied() {
-            Ok(ReadBsonCommand {
+            self.outstanding.remove(&next_id);
+
+            return Ok(ReadBsonCommand {
                 id: next_id,
-                command: self.read_map.shift_remove(&next_id).unwrap()
-            })
-        } else {
-            let read = self.codec.read_async().await?;
-            Ok(read)
+                command: self.read_map.shift_remove(&next_id).unwrap(),
+            });
+        }
+
+        loop {
+            let mut read = self.codec.read_async().await?;
+            if self.discard_abandoned(read.id) {
+                continue;
+            }
+
+            self.interceptors.after_read(&mut read);
+            self.outstanding.remove(&read.id);
+
+            return Ok(read);
         }
     }
 
     /// Read [BsonCommand] response asynchronously
     pub async fn response_async(&mut self, id: i32) -> Result<BsonCommand<Document>, ReadError> {
-        if let Some(read) = self.read_map.shift_remove(&id) {
+        self.reclaim(id)?;
+        self.abandon_cancelled();
+
+        self.in_progress.push(id);
+        let res = with_timeout(self.timeout, self.read_response_async(id)).await;
+        self.finish_in_progress(&res);
+
+        res
+    }
+
+    /// Read responses of every tickets asynchronously.
+    /// Returns responses in same order of ids regardless of arrival order.
+    pub async fn responses_async(
+        &mut self,
+        ids: &[i32],
+    ) -> Result<Vec<BsonCommand<Document>>, ReadError> {
+        for id in ids {
+            self.reclaim(*id)?;
+        }
+        self.abandon_cancelled();
+
+        self.in_progress.extend_from_slice(ids);
+        let timeout = self.timeout;
+        let res = with_timeout(timeout, async {
+            let mut responses = Vec::with_capacity(ids.len());
+            for id in ids {
+                responses.push(self.read_response_async(*id).await?);
+                self.in_progress.retain(|in_progress| in_progress != id);
+            }
+
+            Ok(responses)
+        })
+        .await;
+        self.finish_in_progress(&res);
+
+        res
+    }
+
+    /// Read [BsonCommand] response asynchronously deserializing data directly from frame.
+    /// Other commands are buffered as [Document].
+    pub async fn response_typed_async<D: DeserializeOwned>(
+        &mut self,
+        id: i32,
+    ) -> Result<BsonCommand<D>, ReadError> {
+        self.reclaim(id)?;
+        self.abandon_cancelled();
+
+        self.in_progress.push(id);
+        let res = with_timeout(self.timeout, self.read_response_typed_async(id)).await;
+        self.finish_in_progress(&res);
+
+        res
+    }
+
+    async fn read_response_async(&mut self, id: i32) -> Result<BsonCommand<Document>, ReadError> {
+        if let Some(mut read) = self.read_map.shift_remove(&id) {
+            self.outstanding.remove(&id);
+            self.interceptors.on_response(id, &mut read);
             return Ok(read);
         }
 
         loop {
-            let ReadBsonCommand { id: request_id, command } = self.codec.read_async().await?;
+            self.check_backpressure()?;
+
+            let mut read = self.codec.read_async().await?;
+            if self.discard_abandoned(read.id) {
+                continue;
+            }
+
+            self.interceptors.after_read(&mut read);
+
+            let ReadBsonCommand { id: request_id, mut command } = read;
 
             if request_id == id {
+                self.outstanding.remove(&id);
+                self.interceptors.on_response(id, &mut command);
                 return Ok(command);
             } else {
-                self.read_map.insert(request_id, command);
+                self.buffer_command(request_id, command)?;
+            }
+        }
+    }
+
+    async fn read_response_typed_async<D: DeserializeOwned>(
+        &mut self,
+        id: i32,
+    ) -> Result<BsonCommand<D>, ReadError> {
+        if !self.interceptors.is_empty() || self.read_map.contains_key(&id) {
+            return deserialize_command(self.read_response_async(id).await?);
+        }
+
+        loop {
+            self.check_backpressure()?;
+
+            let command = self.codec.read_raw_async().await?;
+            if self.discard_abandoned(command.header.id) {
+                continue;
+            }
+
+            if command.header.id == id {
+                self.outstanding.remove(&id);
+                return Ok(decode_command(command)?.command);
+            } else {
+                let ReadBsonCommand { id: request_id, command } = decode_command(command)?;
+                self.buffer_command(request_id, command)?;
+            }
+        }
+    }
+}
+
+/// Fail with [ReadError::Timeout] if future does not complete in time
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    fut: impl Future<Output = Result<T, ReadError>>,
+) -> Result<T, ReadError> {
+    match timeout {
+        Some(timeout) => {
+            pin_mut!(fut);
+
+            match future::select(fut, Delay::new(timeout)).await {
+                Either::Left((res, _)) => res,
+                Either::Right(_) => Err(ReadError::Timeout),
             }
         }
+
+        None => fut.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Cursor, Read, Write},
+        time::Duration,
+    };
+
+    use bson::Document;
+    use futures::FutureExt;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    use crate::command::{
+        codec::{BsonCommandCodec, ReadError},
+        BsonCommand,
+    };
+
+    use super::{BsonCommandSession, OverflowPolicy};
+
+    /// Stream reading prepared commands and ignoring written data
+    struct PreparedStream(Cursor<Vec<u8>>);
+
+    impl Read for PreparedStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    impl Write for PreparedStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn command(method: &'static str) -> BsonCommand<Document> {
+        BsonCommand::new_const(method, 0, Document::new())
+    }
+
+    /// Session with requests of id 0 to count - 1 reading commands of given ids
+    fn prepared_session(
+        count: usize,
+        ids: &[i32],
+        policy: OverflowPolicy,
+    ) -> BsonCommandSession<PreparedStream> {
+        let mut codec = BsonCommandCodec::new(Vec::new());
+        for id in ids {
+            codec.write(*id, &command("MSG")).unwrap();
+        }
+
+        let mut session = BsonCommandSession::new(PreparedStream(Cursor::new(codec.into_inner())));
+        session.set_buffer_capacity(Some(1));
+        session.set_overflow_policy(policy);
+
+        for _ in 0..count {
+            session.request(&command("PING")).unwrap();
+        }
+
+        session
+    }
+
+    #[test]
+    fn drop_oldest_evicts_unsolicited() {
+        let mut session = prepared_session(1, &[100, 101, 0], OverflowPolicy::DropOldest);
+
+        session.response(0).unwrap();
+        assert_eq!(session.read().unwrap().id, 101);
+        assert_eq!(session.buffer_stats().dropped, 1);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_pending_responses() {
+        let mut session = prepared_session(3, &[1, 2, 0], OverflowPolicy::DropOldest);
+
+        session.response(0).unwrap();
+        assert_eq!(session.buffered_len(), 2);
+
+        session.response(1).unwrap();
+        session.response(2).unwrap();
+        assert_eq!(session.buffer_stats().dropped, 0);
+    }
+
+    #[test]
+    fn error_drops_unsolicited() {
+        let mut session = prepared_session(1, &[100, 101, 0], OverflowPolicy::Error);
+
+        assert!(matches!(session.response(0), Err(ReadError::BufferFull)));
+        assert_eq!(session.buffer_stats().dropped, 1);
+
+        session.response(0).unwrap();
+        assert_eq!(session.read().unwrap().id, 100);
+    }
+
+    #[test]
+    fn error_keeps_pending_responses() {
+        let mut session = prepared_session(2, &[100, 1, 0], OverflowPolicy::Error);
+
+        session.response(0).unwrap();
+        session.response(1).unwrap();
+        assert_eq!(session.read().unwrap().id, 100);
+        assert_eq!(session.buffer_stats().dropped, 0);
+    }
+
+    #[test]
+    fn backpressure_stops_reading() {
+        let mut session = prepared_session(1, &[100, 0], OverflowPolicy::Backpressure);
+
+        assert!(matches!(session.response(0), Err(ReadError::BufferFull)));
+        assert_eq!(session.read().unwrap().id, 100);
+
+        session.response(0).unwrap();
+        assert_eq!(session.buffer_stats().dropped, 0);
+    }
+
+    #[tokio::test]
+    async fn retry_after_cancel() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut session = BsonCommandSession::new(client.compat());
+        let mut server = BsonCommandCodec::new(server.compat());
+
+        let id = session.request_async(&command("PING")).await.unwrap();
+        assert!(session.response_async(id).now_or_never().is_none());
+
+        server.write_async(id, &command("PING")).await.unwrap();
+        assert_eq!(session.response_async(id).await.unwrap().method, "PING");
+    }
+
+    #[tokio::test]
+    async fn retry_after_timeout() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut session = BsonCommandSession::new(client.compat());
+        session.set_timeout(Some(Duration::from_millis(10)));
+        let mut server = BsonCommandCodec::new(server.compat());
+
+        let id = session.request_async(&command("PING")).await.unwrap();
+        assert!(matches!(
+            session.response_async(id).await,
+            Err(ReadError::Timeout)
+        ));
+
+        server.write_async(id, &command("PING")).await.unwrap();
+        assert_eq!(session.response_async(id).await.unwrap().method, "PING");
+    }
+
+    #[tokio::test]
+    async fn retry_after_discard_fails() {
+        let (client, server) = tokio::io::duplex(4096);
+        let mut session = BsonCommandSession::new(client.compat());
+        session.set_timeout(Some(Duration::from_millis(10)));
+        let mut server = BsonCommandCodec::new(server.compat());
+
+        let id = session.request_async(&command("PING")).await.unwrap();
+        assert!(matches!(
+            session.response_async(id).await,
+            Err(ReadError::Timeout)
+        ));
+
+        let next_id = session.request_async(&command("PING")).await.unwrap();
+        assert_ne!(next_id, id);
+
+        server.write_async(id, &command("PING")).await.unwrap();
+        server.write_async(next_id, &command("PING")).await.unwrap();
+
+        session.response_async(next_id).await.unwrap();
+        assert_eq!(session.buffer_stats().discarded, 1);
+
+        assert!(matches!(
+            session.response_async(id).await,
+            Err(ReadError::NotOutstanding)
+        ));
     }
 }
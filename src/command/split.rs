@@ -0,0 +1,270 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bson::Document;
+use futures::{
+    channel::oneshot,
+    io::{ReadHalf, WriteHalf},
+    AsyncRead, AsyncReadExt, AsyncWrite, Future, FutureExt,
+};
+use indexmap::IndexMap;
+use loco_protocol::command::codec::StreamError;
+use serde::Serialize;
+
+use super::{
+    codec::{BsonCommandCodec, CodecBuffers, ReadError, WriteError},
+    id::IdAllocator,
+    interceptor::InterceptorChain,
+    session::{BsonCommandSession, RequestError, SessionParts},
+    BsonCommand, ReadBsonCommand,
+};
+
+type ResponseSender = oneshot::Sender<BsonCommand<Document>>;
+
+/// State shared by [SessionWriter] and [SessionReader]
+#[derive(Debug)]
+struct SplitState {
+    id_allocator: IdAllocator,
+    pending_map: HashMap<i32, ResponseSender>,
+
+    interceptors: InterceptorChain,
+
+    /// True if [SessionReader] is dropped
+    closed: bool,
+}
+
+/// Future resolving to response of request sent by [SessionWriter].
+/// Response is routed by [SessionReader] so it must be read concurrently.
+#[derive(Debug)]
+pub struct ResponseTicket {
+    id: i32,
+    receiver: oneshot::Receiver<BsonCommand<Document>>,
+}
+
+impl ResponseTicket {
+    /// Request id of this ticket
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl Future for ResponseTicket {
+    type Output = Result<BsonCommand<Document>, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver
+            .poll_unpin(cx)
+            .map_err(|_| RequestError::closed())
+    }
+}
+
+/// Writing half of [BsonCommandSession].
+/// Allocates request id and encodes commands.
+#[derive(Debug)]
+pub struct SessionWriter<S> {
+    state: Arc<Mutex<SplitState>>,
+    codec: BsonCommandCodec<WriteHalf<S>>,
+}
+
+impl<S: AsyncWrite> SessionWriter<S> {
+    /// Send request and create [ResponseTicket] of it.
+    /// Ticket fails with [super::session::RequestErrorKind::Closed] if [SessionReader] is dropped.
+    pub async fn request_async(
+        &mut self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<ResponseTicket, WriteError> {
+        let ticket = self.write_request_async(command).await?;
+        self.codec
+            .flush_async()
+            .await
+            .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+        Ok(ticket)
+    }
+
+    /// Send every commands flushing once.
+    /// Returns tickets in same order of commands.
+    pub async fn request_batch_async<'a, T: Serialize + 'a>(
+        &mut self,
+        commands: impl IntoIterator<Item = &'a BsonCommand<T>>,
+    ) -> Result<Vec<ResponseTicket>, WriteError> {
+        let mut tickets = Vec::new();
+        for command in commands {
+            tickets.push(self.write_request_async(command).await?);
+        }
+
+        self.codec
+            .flush_async()
+            .await
+            .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+
+        Ok(tickets)
+    }
+
+    async fn write_request_async(
+        &mut self,
+        command: &BsonCommand<impl Serialize>,
+    ) -> Result<ResponseTicket, WriteError> {
+        let (sender, receiver) = oneshot::channel();
+
+        let (request_id, intercepted) = {
+            let mut state = self.state.lock().unwrap();
+
+            let SplitState {
+                id_allocator,
+                pending_map,
+                ..
+            } = &mut *state;
+            let request_id = id_allocator
+                .allocate(|id| pending_map.contains_key(&id))
+                .ok_or(WriteError::IdExhausted)?;
+
+            let intercepted = if state.interceptors.is_empty() {
+                None
+            } else {
+                let mut command = BsonCommand {
+                    method: command.method.clone(),
+                    data_type: command.data_type,
+                    data: bson::to_document(&command.data)?,
+                };
+                state.interceptors.before_write(request_id, &mut command);
+
+                Some(command)
+            };
+
+            // Reader is gone. Dropping sender fails ticket.
+            if !state.closed {
+                state.pending_map.insert(request_id, sender);
+            }
+
+            (request_id, intercepted)
+        };
+
+        let res = match intercepted {
+            Some(command) => self.codec.write_async(request_id, &command).await,
+            None => self.codec.write_async(request_id, command).await,
+        };
+
+        if let Err(err) = res {
+            self.state.lock().unwrap().pending_map.remove(&request_id);
+            return Err(err);
+        }
+
+        Ok(ResponseTicket {
+            id: request_id,
+            receiver,
+        })
+    }
+}
+
+/// Reading half of [BsonCommandSession].
+/// Routes responses to [ResponseTicket] and returns other commands.
+#[derive(Debug)]
+pub struct SessionReader<S> {
+    state: Arc<Mutex<SplitState>>,
+
+    /// Commands read before split
+    read_map: IndexMap<i32, BsonCommand<Document>>,
+
+    codec: BsonCommandCodec<ReadHalf<S>>,
+}
+
+impl<S: AsyncRead> SessionReader<S> {
+    /// Read next command which is not response of pending request.
+    /// Responses read while waiting are passed to their [ResponseTicket].
+    pub async fn read_async(&mut self) -> Result<ReadBsonCommand<Document>, ReadError> {
+        if let Some(next_id) = self.read_map.keys().next().copied() {
+            return Ok(ReadBsonCommand {
+                id: next_id,
+                command: self.read_map.shift_remove(&next_id).unwrap(),
+            });
+        }
+
+        loop {
+            let mut read = self.codec.read_async().await?;
+
+            let mut state = self.state.lock().unwrap();
+            state.interceptors.after_read(&mut read);
+
+            match state.pending_map.remove(&read.id) {
+                Some(sender) => {
+                    let ReadBsonCommand { id, mut command } = read;
+                    state.interceptors.on_response(id, &mut command);
+
+                    // Ticket may be dropped already
+                    sender.send(command).ok();
+                }
+
+                None => return Ok(read),
+            }
+        }
+    }
+}
+
+impl<S> Drop for SessionReader<S> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.closed = true;
+            state.pending_map.clear();
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite> BsonCommandSession<S> {
+    /// Split session into [SessionWriter] and [SessionReader] so they can be used on different tasks.
+    /// Halves share request id counter, pending requests and interceptors.
+    /// Commands buffered before split are returned by [SessionReader::read_async] first.
+    /// Partially read frame and unwritten data are kept.
+    pub fn split(self) -> (SessionWriter<S>, SessionReader<S>) {
+        let SessionParts {
+            id_allocator,
+            read_map,
+            interceptors,
+            stream,
+            buffers,
+            ..
+        } = self.into_parts();
+        let (read_half, write_half) = stream.split();
+
+        let state = Arc::new(Mutex::new(SplitState {
+            id_allocator,
+            pending_map: HashMap::new(),
+            interceptors,
+            closed: false,
+        }));
+
+        (
+            SessionWriter {
+                state: state.clone(),
+                codec: BsonCommandCodec::from_parts(
+                    write_half,
+                    CodecBuffers {
+                        read_buf: Vec::new(),
+                        write_buf: buffers.write_buf,
+                    },
+                ),
+            },
+            SessionReader {
+                state,
+                read_map,
+                codec: BsonCommandCodec::from_parts(
+                    read_half,
+                    CodecBuffers {
+                        read_buf: buffers.read_buf,
+                        write_buf: Vec::new(),
+                    },
+                ),
+            },
+        )
+    }
+}
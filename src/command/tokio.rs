@@ -0,0 +1,97 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! Native tokio io support.
+//! Wraps tokio streams so async codec and session methods can be used without tokio-util compat.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{ready, AsyncRead, AsyncWrite};
+
+use super::{
+    codec::BsonCommandCodec,
+    driver::{BroadcastReceiver, SessionDriver, SessionHandle},
+    session::BsonCommandSession,
+};
+
+/// Stream implementing [AsyncRead], [AsyncWrite] for tokio stream.
+/// Reads are done directly into caller buffer.
+#[derive(Debug)]
+pub struct TokioStream<S>(S);
+
+impl<S> TokioStream<S> {
+    pub const fn new(stream: S) -> Self {
+        Self(stream)
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.0
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S: ::tokio::io::AsyncRead + Unpin> AsyncRead for TokioStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut read_buf = ::tokio::io::ReadBuf::new(buf);
+        ready!(Pin::new(&mut self.get_mut().0).poll_read(cx, &mut read_buf))?;
+
+        Poll::Ready(Ok(read_buf.filled().len()))
+    }
+}
+
+impl<S: ::tokio::io::AsyncWrite + Unpin> AsyncWrite for TokioStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+    }
+}
+
+impl<S> BsonCommandCodec<TokioStream<S>> {
+    /// Create codec using tokio stream
+    pub fn new_tokio(stream: S) -> Self {
+        Self::new(TokioStream::new(stream))
+    }
+}
+
+impl<S> BsonCommandSession<TokioStream<S>> {
+    /// Create session using tokio stream
+    pub fn new_tokio(stream: S) -> Self {
+        Self::new(TokioStream::new(stream))
+    }
+}
+
+impl<S> SessionDriver<TokioStream<S>> {
+    /// Create driver using tokio stream
+    pub fn new_tokio(stream: S) -> (Self, SessionHandle, BroadcastReceiver) {
+        Self::new(TokioStream::new(stream))
+    }
+}
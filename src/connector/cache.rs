@@ -0,0 +1,55 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{fmt::Debug, sync::Mutex};
+
+use crate::{response::checkin::CheckinRes, time::now_millis};
+
+/// Storage for [CheckinRes].
+/// Implement this to persist checkin result between process restarts.
+pub trait CheckinCache: Debug + Send + Sync {
+    /// Returns cached [CheckinRes] if it is not expired
+    fn load(&self) -> Option<CheckinRes>;
+
+    /// Store [CheckinRes]. Expires after [CheckinRes::cache_expire] seconds.
+    fn store(&self, checkin: &CheckinRes);
+
+    /// Remove cached [CheckinRes]
+    fn invalidate(&self);
+}
+
+/// In memory [CheckinCache]
+#[derive(Debug, Default)]
+pub struct MemoryCheckinCache {
+    /// Cached [CheckinRes] with its expire time in unix millis
+    entry: Mutex<Option<(CheckinRes, i64)>>,
+}
+
+impl MemoryCheckinCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckinCache for MemoryCheckinCache {
+    fn load(&self) -> Option<CheckinRes> {
+        match &*self.entry.lock().unwrap() {
+            Some((checkin, expire_at)) if now_millis() < *expire_at => Some(checkin.clone()),
+
+            _ => None,
+        }
+    }
+
+    fn store(&self, checkin: &CheckinRes) {
+        let expire_at = now_millis() + i64::from(checkin.cache_expire.max(0)) * 1000;
+
+        *self.entry.lock().unwrap() = Some((checkin.clone(), expire_at));
+    }
+
+    fn invalidate(&self) {
+        *self.entry.lock().unwrap() = None;
+    }
+}
@@ -0,0 +1,289 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{io, time::Duration};
+
+use futures::{
+    future::{self, Either},
+    pin_mut,
+};
+use futures_timer::Delay;
+
+use crate::{
+    response::{booking::GetConfRes, checkin::CheckinRes},
+    structs::connection::ConnectionData,
+};
+
+use super::{
+    transport::{Endpoint, ServerKind, TransportFactory},
+    ConnectError,
+};
+
+/// IP version preference of [FailoverStrategy]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpPreference {
+    /// Try ipv4 hosts, then ipv6 hosts
+    #[default]
+    PreferV4,
+
+    /// Try ipv6 hosts, then ipv4 hosts
+    PreferV6,
+
+    V4Only,
+    V6Only,
+}
+
+/// Decides endpoints to try and their order
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailoverStrategy {
+    pub preference: IpPreference,
+
+    /// Use cellular(3g) config instead of wifi config
+    pub cellular: bool,
+
+    /// Override [ConnectionData::connection_timeout]
+    pub timeout: Option<Duration>,
+}
+
+impl FailoverStrategy {
+    /// Connection config to use
+    pub fn connection_data<'a>(&self, conf: &'a GetConfRes) -> &'a ConnectionData {
+        if self.cellular {
+            &conf.ceullar
+        } else {
+            &conf.wifi
+        }
+    }
+
+    /// Timeout for each attempt. None if server config has no timeout.
+    pub fn attempt_timeout(&self, conf: &GetConfRes) -> Option<Duration> {
+        self.timeout.or_else(|| {
+            Some(self.connection_data(conf).connection_timeout_duration())
+                .filter(|timeout| !timeout.is_zero())
+        })
+    }
+
+    /// Timeout of requests on connected session. None if server config has no timeout.
+    pub fn request_timeout(&self, conf: &GetConfRes) -> Option<Duration> {
+        Some(self.connection_data(conf).request_timeout_duration())
+            .filter(|timeout| !timeout.is_zero())
+    }
+
+    /// Checkin endpoints in order
+    pub fn checkin_endpoints(&self, conf: &GetConfRes) -> Vec<Endpoint> {
+        let ports = &self.connection_data(conf).ports;
+
+        self.order(&conf.ticket.lsl, &conf.ticket.lsl6)
+            .flat_map(|host| {
+                ports.iter().filter_map(move |port| {
+                    Some(Endpoint::new(
+                        ServerKind::Checkin,
+                        host,
+                        u16::try_from(*port).ok()?,
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Loco endpoints in order
+    pub fn loco_endpoints(&self, checkin: &CheckinRes) -> Vec<Endpoint> {
+        let port = match u16::try_from(checkin.port) {
+            Ok(port) => port,
+            Err(_) => return Vec::new(),
+        };
+
+        self.order(
+            std::slice::from_ref(&checkin.host),
+            std::slice::from_ref(&checkin.host6),
+        )
+        .map(|host| Endpoint::new(ServerKind::Loco, host, port))
+        .collect()
+    }
+
+    fn order<'a>(&self, v4: &'a [String], v6: &'a [String]) -> impl Iterator<Item = &'a String> {
+        let (first, second): (&[String], &[String]) = match self.preference {
+            IpPreference::PreferV4 => (v4, v6),
+            IpPreference::PreferV6 => (v6, v4),
+            IpPreference::V4Only => (v4, &[]),
+            IpPreference::V6Only => (v6, &[]),
+        };
+
+        first
+            .iter()
+            .chain(second.iter())
+            .filter(|host| !host.is_empty())
+    }
+}
+
+/// Try endpoints in order and returns first connected stream with its endpoint
+pub async fn connect_any<F: TransportFactory>(
+    factory: &F,
+    endpoints: &[Endpoint],
+    timeout: Option<Duration>,
+) -> Result<(F::Stream, Endpoint), ConnectError> {
+    let mut errors = Vec::new();
+
+    for endpoint in endpoints {
+        match connect_timeout(factory, endpoint, timeout).await {
+            Ok(stream) => return Ok((stream, endpoint.clone())),
+
+            Err(err) => errors.push((endpoint.clone(), err)),
+        }
+    }
+
+    if errors.is_empty() {
+        Err(ConnectError::NoEndpoint)
+    } else {
+        Err(ConnectError::Exhausted(errors))
+    }
+}
+
+async fn connect_timeout<F: TransportFactory>(
+    factory: &F,
+    endpoint: &Endpoint,
+    timeout: Option<Duration>,
+) -> io::Result<F::Stream> {
+    let connect = factory.connect(endpoint);
+    pin_mut!(connect);
+
+    match timeout {
+        Some(timeout) => match future::select(connect, Delay::new(timeout)).await {
+            Either::Left((res, _)) => res,
+
+            Either::Right(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Connecting to {} timed out", endpoint),
+            )),
+        },
+
+        None => connect.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::{future, io::Cursor};
+
+    use crate::{
+        connector::{
+            transport::{Endpoint, ServerKind, TransportFactory},
+            ConnectError,
+        },
+        response::checkin::CheckinRes,
+    };
+
+    use super::{connect_any, FailoverStrategy, IpPreference};
+
+    /// Transport refusing given hosts and recording every attempt
+    struct RefusingTransport {
+        refused: Vec<&'static str>,
+        attempts: Arc<Mutex<Vec<Endpoint>>>,
+    }
+
+    impl TransportFactory for RefusingTransport {
+        type Stream = Cursor<Vec<u8>>;
+        type Future = future::Ready<io::Result<Self::Stream>>;
+
+        fn connect(&self, endpoint: &Endpoint) -> Self::Future {
+            self.attempts.lock().unwrap().push(endpoint.clone());
+
+            if self.refused.contains(&endpoint.host.as_str()) {
+                future::ready(Err(io::ErrorKind::ConnectionRefused.into()))
+            } else {
+                future::ready(Ok(Cursor::new(Vec::new())))
+            }
+        }
+    }
+
+    fn checkin() -> CheckinRes {
+        CheckinRes {
+            host: "loco.test".into(),
+            host6: "loco6.test".into(),
+            port: 5223,
+            cache_expire: 0,
+            cs_host: String::new(),
+            cs_host6: String::new(),
+            cs_port: 0,
+            vss_host: String::new(),
+            vss_host6: String::new(),
+            vss_port: 0,
+        }
+    }
+
+    #[test]
+    fn loco_endpoints_order() {
+        let endpoints = |preference| {
+            FailoverStrategy {
+                preference,
+                ..Default::default()
+            }
+            .loco_endpoints(&checkin())
+        };
+
+        let v4 = Endpoint::new(ServerKind::Loco, "loco.test", 5223);
+        let v6 = Endpoint::new(ServerKind::Loco, "loco6.test", 5223);
+
+        assert_eq!(endpoints(IpPreference::PreferV4), [v4.clone(), v6.clone()]);
+        assert_eq!(endpoints(IpPreference::PreferV6), [v6.clone(), v4.clone()]);
+        assert_eq!(endpoints(IpPreference::V4Only), [v4]);
+        assert_eq!(endpoints(IpPreference::V6Only), [v6]);
+    }
+
+    #[tokio::test]
+    async fn connect_any_skips_refused() {
+        let endpoints = [
+            Endpoint::new(ServerKind::Checkin, "a.test", 1),
+            Endpoint::new(ServerKind::Checkin, "b.test", 1),
+            Endpoint::new(ServerKind::Checkin, "c.test", 1),
+        ];
+
+        let transport = RefusingTransport {
+            refused: vec!["a.test"],
+            attempts: Arc::default(),
+        };
+
+        let (_, endpoint) = connect_any(&transport, &endpoints, None).await.unwrap();
+
+        assert_eq!(endpoint, endpoints[1]);
+        assert_eq!(*transport.attempts.lock().unwrap(), endpoints[..2]);
+    }
+
+    #[tokio::test]
+    async fn connect_any_exhausted() {
+        let endpoints = [
+            Endpoint::new(ServerKind::Loco, "a.test", 1),
+            Endpoint::new(ServerKind::Loco, "b.test", 1),
+        ];
+
+        let transport = RefusingTransport {
+            refused: vec!["a.test", "b.test"],
+            attempts: Arc::default(),
+        };
+
+        match connect_any(&transport, &endpoints, None).await {
+            Err(ConnectError::Exhausted(errors)) => {
+                let failed = errors.into_iter().map(|(endpoint, _)| endpoint);
+                assert!(failed.eq(endpoints.iter().cloned()));
+            }
+
+            res => panic!(
+                "Expected exhausted error, got {:?}",
+                res.map(|(_, endpoint)| endpoint)
+            ),
+        }
+
+        assert!(matches!(
+            connect_any(&transport, &[], None).await,
+            Err(ConnectError::NoEndpoint)
+        ));
+    }
+}
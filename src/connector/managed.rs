@@ -0,0 +1,243 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{error::Error, fmt::Display};
+
+use bson::Document;
+use futures::{
+    channel::{mpsc, oneshot},
+    future::{self, Either},
+    pin_mut, StreamExt,
+};
+
+use crate::{
+    command::{
+        codec::ReadError,
+        driver::{RequestReceiver, SessionDriver, SessionHandle, SharedTimeout},
+        BsonCommand, ReadBsonCommand,
+    },
+    response::chat::{Kickout, LoginListRes},
+};
+
+use super::{
+    transport::{Endpoint, TransportFactory},
+    ConnectError, LocoConnector,
+};
+
+/// Reason of server migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationReason {
+    /// CHANGESVR received
+    ChangeSvr,
+
+    /// KICKOUT received with change server reason
+    Kickout,
+}
+
+/// Event emitted by [ManagedSession]
+#[derive(Debug, Clone)]
+pub enum ManagedEvent {
+    /// Logged in to loco server. Emitted on first connection and after each migration.
+    Connected {
+        endpoint: Endpoint,
+        login: LoginListRes,
+    },
+
+    /// Server migration started.
+    /// Requests sent before fail with [crate::command::session::RequestErrorKind::Closed],
+    /// requests sent after are delivered to new server.
+    Migrating(MigrationReason),
+
+    /// Command pushed by server
+    Broadcast(BsonCommand<Document>),
+}
+
+pub type ManagedEventReceiver = mpsc::UnboundedReceiver<ManagedEvent>;
+
+#[derive(Debug)]
+pub enum ManagedError {
+    Connect(ConnectError),
+    Read(ReadError),
+}
+
+impl From<ConnectError> for ManagedError {
+    fn from(err: ConnectError) -> Self {
+        Self::Connect(err)
+    }
+}
+
+impl From<ReadError> for ManagedError {
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl Display for ManagedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagedError::Connect(err) => err.fmt(f),
+            ManagedError::Read(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ManagedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ManagedError::Connect(err) => Some(err),
+            ManagedError::Read(err) => Some(err),
+        }
+    }
+}
+
+/// Session follows server migration requested by CHANGESVR or KICKOUT.
+/// Connects again using [LocoConnector] and logs in with same tokens.
+/// Timeout of [SessionHandle] is set from server config unless changed by user.
+///
+/// The session does nothing until [ManagedSession::run] future is polled.
+#[derive(Debug)]
+pub struct ManagedSession<F> {
+    connector: LocoConnector<F>,
+
+    receiver: RequestReceiver,
+    event_sender: mpsc::UnboundedSender<ManagedEvent>,
+
+    timeout: SharedTimeout,
+    closed_sender: oneshot::Sender<()>,
+}
+
+impl<F> ManagedSession<F> {
+    /// Create new [ManagedSession] with [SessionHandle] stays usable across migrations
+    pub fn new(connector: LocoConnector<F>) -> (Self, SessionHandle, ManagedEventReceiver) {
+        let (handle, receiver, closed_sender) = SessionHandle::channel();
+        let (event_sender, event_receiver) = mpsc::unbounded();
+
+        (
+            Self {
+                connector,
+                receiver,
+                event_sender,
+                timeout: handle.shared_timeout(),
+                closed_sender,
+            },
+            handle,
+            event_receiver,
+        )
+    }
+}
+
+impl<F: TransportFactory> ManagedSession<F> {
+    /// Connect and run session.
+    /// Returns Ok if every [SessionHandle] is dropped, Err if connection or migration fails.
+    pub async fn run(self) -> Result<(), ManagedError> {
+        let Self {
+            connector,
+            mut receiver,
+            event_sender,
+            timeout,
+            // Notify handles on drop
+            closed_sender: _closed_sender,
+        } = self;
+
+        // Timeout applied from last server config
+        let mut applied_timeout = None;
+
+        loop {
+            let connection = connector.connect().await?;
+
+            {
+                let mut timeout = timeout.lock().unwrap();
+
+                // Keep timeout set by user
+                if *timeout == applied_timeout {
+                    applied_timeout = connection.session.timeout();
+                    *timeout = applied_timeout;
+                }
+            }
+
+            event_sender
+                .unbounded_send(ManagedEvent::Connected {
+                    endpoint: connection.endpoint,
+                    login: connection.login,
+                })
+                .ok();
+
+            let (driver, handle, mut broadcasts) = SessionDriver::from_session(connection.session);
+
+            let driver_task = driver.run();
+
+            let forward_task = async {
+                while let Some(request) = receiver.next().await {
+                    handle.forward(request);
+                }
+            };
+
+            let broadcast_task = async {
+                while let Some(ReadBsonCommand { command, .. }) = broadcasts.next().await {
+                    let reason = migration_reason(&command);
+
+                    event_sender
+                        .unbounded_send(ManagedEvent::Broadcast(command))
+                        .ok();
+
+                    if reason.is_some() {
+                        return reason;
+                    }
+                }
+
+                None
+            };
+
+            pin_mut!(driver_task, forward_task, broadcast_task);
+
+            let reason =
+                match future::select(driver_task, future::select(forward_task, broadcast_task))
+                    .await
+                {
+                    // Driver stopped. Check broadcasts read before server closed connection.
+                    Either::Left((res, rest)) => match rest.await {
+                        Either::Right((Some(reason), _)) => reason,
+
+                        _ => {
+                            res?;
+                            return Ok(());
+                        }
+                    },
+
+                    // Every handle is dropped
+                    Either::Right((Either::Left(_), _)) => return Ok(()),
+
+                    Either::Right((Either::Right((reason, _)), _)) => match reason {
+                        Some(reason) => reason,
+
+                        // Driver stopped
+                        None => return Ok(()),
+                    },
+                };
+
+            // Cached server is no longer valid
+            connector.invalidate_checkin();
+
+            event_sender
+                .unbounded_send(ManagedEvent::Migrating(reason))
+                .ok();
+        }
+    }
+}
+
+fn migration_reason(command: &BsonCommand<Document>) -> Option<MigrationReason> {
+    match &*command.method {
+        "CHANGESVR" => Some(MigrationReason::ChangeSvr),
+
+        "KICKOUT" => match bson::from_document::<Kickout>(command.data.clone()) {
+            Ok(Kickout { reason: 2 }) => Some(MigrationReason::Kickout),
+
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
@@ -0,0 +1,607 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! Connection bootstrap from booking to login
+
+pub mod cache;
+pub mod failover;
+pub mod managed;
+pub mod transport;
+
+#[cfg(feature = "tokio-native-tls")]
+pub mod tokio;
+
+use std::{error::Error, fmt::Display, io, sync::Arc};
+
+use crate::{
+    client::{
+        booking::BookingClient, checkin::CheckinClient, talk::TalkClient, LocoError, RequestError,
+    },
+    command::session::BsonCommandSession,
+    request::{
+        booking::GetConfReq,
+        chat::{LChatListReq, LoginListReq},
+        checkin::CheckinReq,
+    },
+    response::{booking::GetConfRes, chat::LoginListRes, checkin::CheckinRes},
+    structs::client::ClientInfo,
+};
+
+use self::{
+    cache::CheckinCache,
+    failover::{connect_any, FailoverStrategy},
+    transport::{Endpoint, ServerKind, TransportFactory},
+};
+
+#[derive(Debug)]
+pub enum ConnectError {
+    Transport(io::Error),
+    Request(RequestError),
+
+    /// Server responded with non success status or without data
+    Response {
+        method: &'static str,
+        error: LocoError,
+    },
+
+    /// Server config does not contain usable endpoint
+    NoEndpoint,
+
+    /// Every endpoint failed to connect
+    Exhausted(Vec<(Endpoint, io::Error)>),
+}
+
+impl From<io::Error> for ConnectError {
+    fn from(err: io::Error) -> Self {
+        Self::Transport(err)
+    }
+}
+
+impl From<RequestError> for ConnectError {
+    fn from(err: RequestError) -> Self {
+        Self::Request(err)
+    }
+}
+
+impl Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectError::Transport(err) => err.fmt(f),
+            ConnectError::Request(err) => err.fmt(f),
+            ConnectError::Response { method, error } => write!(f, "{} failed. {}", method, error),
+            ConnectError::NoEndpoint => write!(f, "No usable endpoint"),
+            ConnectError::Exhausted(errors) => {
+                write!(f, "Every endpoint failed to connect.")?;
+
+                for (endpoint, err) in errors {
+                    write!(f, " {}: {}", endpoint, err)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ConnectError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConnectError::Transport(err) => Some(err),
+            ConnectError::Request(err) => Some(err),
+            ConnectError::Response { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+type Logon<S> = (BsonCommandSession<S>, Endpoint, LoginListRes);
+
+/// Logged in loco connection
+#[derive(Debug)]
+pub struct LocoConnection<S> {
+    /// Logged in session. Timeout is set from server config.
+    pub session: BsonCommandSession<S>,
+
+    /// Connected loco server
+    pub endpoint: Endpoint,
+
+    pub conf: GetConfRes,
+    pub checkin: CheckinRes,
+    pub login: LoginListRes,
+}
+
+/// Connects to loco server doing GETCONF, CHECKIN, LOGINLIST in order
+#[derive(Debug, Clone)]
+pub struct LocoConnector<F> {
+    factory: F,
+
+    client: ClientInfo,
+    device_uuid: String,
+    oauth_token: String,
+
+    booking_host: String,
+    booking_port: u16,
+
+    strategy: FailoverStrategy,
+    checkin_cache: Option<Arc<dyn CheckinCache>>,
+
+    user_id: i64,
+    model: String,
+    language: String,
+    country_iso: String,
+    device_type: i8,
+    use_sub: bool,
+    background: bool,
+}
+
+impl<F> LocoConnector<F> {
+    pub fn new(
+        factory: F,
+        client: ClientInfo,
+        device_uuid: impl Into<String>,
+        oauth_token: impl Into<String>,
+    ) -> Self {
+        Self {
+            factory,
+
+            client,
+            device_uuid: device_uuid.into(),
+            oauth_token: oauth_token.into(),
+
+            booking_host: "booking-loco.kakao.com".into(),
+            booking_port: 443,
+
+            strategy: FailoverStrategy::default(),
+            checkin_cache: None,
+
+            user_id: 1,
+            model: String::new(),
+            language: "ko".into(),
+            country_iso: "KR".into(),
+            device_type: 2,
+            use_sub: true,
+            background: false,
+        }
+    }
+
+    /// Booking server address. Default is booking-loco.kakao.com:443
+    pub fn booking(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.booking_host = host.into();
+        self.booking_port = port;
+        self
+    }
+
+    /// Strategy for choosing checkin and loco server endpoints
+    pub fn strategy(mut self, strategy: FailoverStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Reuse checkin result until it expires
+    pub fn checkin_cache(mut self, cache: Arc<dyn CheckinCache>) -> Self {
+        self.checkin_cache = Some(cache);
+        self
+    }
+
+    /// Client user id used on checkin. Any numbers work.
+    pub fn user_id(mut self, user_id: i64) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    /// Device model (mobile only)
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    pub fn country_iso(mut self, country_iso: impl Into<String>) -> Self {
+        self.country_iso = country_iso.into();
+        self
+    }
+
+    /// Device type (2 for pc)
+    pub fn device_type(mut self, device_type: i8) -> Self {
+        self.device_type = device_type;
+        self
+    }
+
+    /// Subdevice(PC, Tablet) or not
+    pub fn use_sub(mut self, use_sub: bool) -> Self {
+        self.use_sub = use_sub;
+        self
+    }
+
+    /// Login as background
+    pub fn background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn factory(&self) -> &F {
+        &self.factory
+    }
+
+    pub fn client(&self) -> &ClientInfo {
+        &self.client
+    }
+
+    /// Remove cached checkin result.
+    /// Call this after receiving [crate::response::chat::ChangeSvr].
+    pub fn invalidate_checkin(&self) {
+        if let Some(cache) = &self.checkin_cache {
+            cache.invalidate();
+        }
+    }
+}
+
+impl<F: TransportFactory> LocoConnector<F> {
+    /// Do GETCONF, CHECKIN, LOGINLIST and returns logged in connection
+    pub async fn connect(&self) -> Result<LocoConnection<F::Stream>, ConnectError> {
+        let conf = self.get_conf().await?;
+
+        let cached = self.checkin_cache.as_ref().and_then(|cache| cache.load());
+        let (checkin, (session, endpoint, login)) = match cached {
+            Some(checkin) => match self.login(&conf, &checkin).await {
+                Ok(logon) => (checkin, logon),
+
+                // Cached server may be unavailable. Retry with fresh checkin.
+                Err(_) => {
+                    self.invalidate_checkin();
+                    self.checkin_login(&conf).await?
+                }
+            },
+
+            None => self.checkin_login(&conf).await?,
+        };
+
+        Ok(LocoConnection {
+            session,
+            endpoint,
+            conf,
+            checkin,
+            login,
+        })
+    }
+
+    async fn checkin_login(
+        &self,
+        conf: &GetConfRes,
+    ) -> Result<(CheckinRes, Logon<F::Stream>), ConnectError> {
+        let checkin = self.checkin(conf).await?;
+        if let Some(cache) = &self.checkin_cache {
+            cache.store(&checkin);
+        }
+
+        match self.login(conf, &checkin).await {
+            Ok(logon) => Ok((checkin, logon)),
+
+            Err(err) => {
+                self.invalidate_checkin();
+                Err(err)
+            }
+        }
+    }
+
+    /// Request server config to booking server
+    pub async fn get_conf(&self) -> Result<GetConfRes, ConnectError> {
+        let stream = self
+            .factory
+            .connect(&Endpoint::new(
+                ServerKind::Booking,
+                &self.booking_host,
+                self.booking_port,
+            ))
+            .await?;
+        let mut session = BsonCommandSession::new(stream);
+
+        BookingClient(&mut session)
+            .get_conf(&GetConfReq {
+                mccmnc: self.client.mccmnc.clone(),
+                os: self.client.os.clone(),
+                model: self.model.clone(),
+            })
+            .await
+            .map_err(response_error("GETCONF"))
+    }
+
+    /// Request loco server address to checkin server in config
+    pub async fn checkin(&self, conf: &GetConfRes) -> Result<CheckinRes, ConnectError> {
+        let (stream, _) = connect_any(
+            &self.factory,
+            &self.strategy.checkin_endpoints(conf),
+            self.strategy.attempt_timeout(conf),
+        )
+        .await?;
+        let mut session = BsonCommandSession::new(stream);
+        session.set_timeout(self.strategy.request_timeout(conf));
+
+        CheckinClient(&mut session)
+            .checkin(&CheckinReq {
+                user_id: self.user_id,
+                client: self.client.clone(),
+                language: self.language.clone(),
+                country_iso: self.country_iso.clone(),
+                use_sub: self.use_sub,
+            })
+            .await
+            .map_err(response_error("CHECKIN"))
+    }
+
+    /// Connect to loco server and login.
+    /// Returns session with connected endpoint. Session timeout is set from server config.
+    pub async fn login(
+        &self,
+        conf: &GetConfRes,
+        checkin: &CheckinRes,
+    ) -> Result<(BsonCommandSession<F::Stream>, Endpoint, LoginListRes), ConnectError> {
+        let (stream, endpoint) = connect_any(
+            &self.factory,
+            &self.strategy.loco_endpoints(checkin),
+            self.strategy.attempt_timeout(conf),
+        )
+        .await?;
+        let mut session = BsonCommandSession::new(stream);
+        session.set_timeout(self.strategy.request_timeout(conf));
+
+        let login = TalkClient(&mut session)
+            .login(&LoginListReq {
+                client: self.client.clone(),
+                protocol_version: "1".into(),
+                device_uuid: self.device_uuid.clone(),
+                oauth_token: self.oauth_token.clone(),
+                language: self.language.clone(),
+                device_type: self.device_type,
+                revision: 0,
+                rp: (),
+                chat_list: LChatListReq {
+                    chat_ids: Vec::new(),
+                    max_ids: Vec::new(),
+                    last_token_id: 0,
+                    last_chat_id: None,
+                },
+                last_block_token: 0,
+                background: self.background,
+            })
+            .await
+            .map_err(response_error("LOGINLIST"))?;
+
+        Ok((session, endpoint, login))
+    }
+}
+
+/// Map client method error of given method
+fn response_error(method: &'static str) -> impl FnOnce(LocoError) -> ConnectError {
+    move |error| match error {
+        LocoError::Request(err) => ConnectError::Request(err),
+        error => ConnectError::Response { method, error },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use bson::{doc, Document};
+    use futures::future;
+    use tokio::io::DuplexStream;
+    use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
+
+    use crate::{
+        command::{codec::BsonCommandCodec, BsonCommand, ReadBsonCommand},
+        response::ResponseData,
+        structs::client::ClientInfo,
+    };
+
+    use super::{
+        transport::{Endpoint, ServerKind, TransportFactory},
+        LocoConnector,
+    };
+
+    type Responses = Arc<HashMap<&'static str, Document>>;
+
+    type Methods = Arc<Mutex<Vec<String>>>;
+
+    /// In memory transport serving every endpoint with same responses
+    #[derive(Clone)]
+    struct MemoryTransport {
+        responses: Responses,
+        methods: Methods,
+
+        refused: Vec<Endpoint>,
+        attempts: Arc<Mutex<Vec<Endpoint>>>,
+    }
+
+    impl TransportFactory for MemoryTransport {
+        type Stream = Compat<DuplexStream>;
+        type Future = future::Ready<io::Result<Self::Stream>>;
+
+        fn connect(&self, endpoint: &Endpoint) -> Self::Future {
+            self.attempts.lock().unwrap().push(endpoint.clone());
+
+            if self.refused.contains(endpoint) {
+                return future::ready(Err(io::ErrorKind::ConnectionRefused.into()));
+            }
+
+            let (client, server) = tokio::io::duplex(4096);
+            tokio::spawn(serve(
+                server.compat(),
+                self.responses.clone(),
+                self.methods.clone(),
+            ));
+
+            future::ready(Ok(client.compat()))
+        }
+    }
+
+    /// Respond to every request with response data of its method
+    async fn serve(stream: Compat<DuplexStream>, responses: Responses, methods: Methods) {
+        let mut codec = BsonCommandCodec::new(stream);
+
+        while let Ok(ReadBsonCommand { id, command }) = codec.read_async().await {
+            methods.lock().unwrap().push(command.method.to_string());
+
+            let response = BsonCommand {
+                data: ResponseData {
+                    status: 0,
+                    data: responses.get(&*command.method).cloned(),
+                },
+                method: command.method,
+                data_type: command.data_type,
+            };
+
+            codec.write_async(id, &response).await.unwrap();
+            codec.flush_async().await.unwrap();
+        }
+    }
+
+    fn connection_data() -> Document {
+        doc! {
+            "bgKeepItv": 0,
+            "bgReconnItv": 0,
+            "bgPingItv": 0,
+            "fgPingItv": 0,
+            "reqTimeout": 0,
+            "encType": 2,
+            "connTimeout": 0,
+            "recvHeaderTimeout": 0,
+            "inSegTimeout": 0,
+            "outSegTimeout": 0,
+            "blockSendBufSize": 0,
+            "ports": [5228],
+        }
+    }
+
+    fn get_conf() -> Document {
+        doc! {
+            "revision": 0,
+            "3g": connection_data(),
+            "wifi": connection_data(),
+            "ticket": {
+                "ssl": [],
+                "v2sl": [],
+                "lsl": ["checkin.test"],
+                "lsl6": [],
+            },
+            "trailer": {
+                "tokenExpireTime": 0,
+                "resolution": 0,
+                "resolutionHD": 0,
+                "compRatio": 0,
+                "compRatioHD": 0,
+                "downMode": 0,
+                "concurrentDownLimit": 0,
+                "concurrentUpLimit": 0,
+                "maxRelaySize": 0,
+                "downCheckSize": 0,
+                "upMaxSize": 0,
+                "videoUpMaxSize": 0,
+                "vCodec": 0,
+                "vFps": 0,
+                "aCodec": 0,
+                "contentExpireTime": 0,
+                "vResolution": 0,
+                "vBitrate": 0,
+                "aFrequency": 0,
+            },
+            "trailer.h": {
+                "vResolution": 0,
+                "vBitrate": 0,
+                "aFrequency": 0,
+            },
+        }
+    }
+
+    fn checkin() -> Document {
+        doc! {
+            "host": "loco.test",
+            "host6": "loco6.test",
+            "port": 5223,
+            "cacheExpire": 0,
+            "cshost": "",
+            "cshost6": "",
+            "csport": 0,
+            "vsshost": "",
+            "vsshost6": "",
+            "vssport": 0,
+        }
+    }
+
+    fn login_list() -> Document {
+        doc! {
+            "userId": 1_i64,
+            "chatDatas": [],
+            "delChatIds": [],
+            "eof": true,
+            "lastTokenId": 0_i64,
+            "minLogId": 0_i64,
+            "ltk": 0_i64,
+            "lbk": 0_i64,
+            "mcmRevision": 0_i64,
+            "revision": 0,
+            "revisionInfo": "",
+            "sb": 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_in_order() {
+        let methods = Methods::default();
+
+        let transport = MemoryTransport {
+            responses: Arc::new(HashMap::from([
+                ("GETCONF", get_conf()),
+                ("CHECKIN", checkin()),
+                ("LOGINLIST", login_list()),
+            ])),
+            methods: methods.clone(),
+            refused: vec![Endpoint::new(ServerKind::Loco, "loco.test", 5223)],
+            attempts: Arc::default(),
+        };
+
+        let connector = LocoConnector::new(
+            transport.clone(),
+            ClientInfo {
+                os: "win32".into(),
+                net_type: 0,
+                app_version: "3.2.8".into(),
+                mccmnc: "999".into(),
+            },
+            "uuid",
+            "token",
+        );
+
+        let connection = connector.connect().await.unwrap();
+
+        assert_eq!(
+            *methods.lock().unwrap(),
+            ["GETCONF", "CHECKIN", "LOGINLIST"]
+        );
+        assert_eq!(
+            *transport.attempts.lock().unwrap(),
+            [
+                Endpoint::new(ServerKind::Booking, "booking-loco.kakao.com", 443),
+                Endpoint::new(ServerKind::Checkin, "checkin.test", 5228),
+                Endpoint::new(ServerKind::Loco, "loco.test", 5223),
+                Endpoint::new(ServerKind::Loco, "loco6.test", 5223),
+            ]
+        );
+        assert_eq!(
+            connection.endpoint,
+            Endpoint::new(ServerKind::Loco, "loco6.test", 5223)
+        );
+        assert_eq!(connection.login.user_id, 1);
+    }
+}
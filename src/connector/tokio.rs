@@ -0,0 +1,90 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! [TransportFactory] using tokio tcp stream.
+//! Enabled by `tokio-native-tls` feature.
+
+use std::{io, sync::Arc};
+
+use ::tokio::{io::BufStream, net::TcpStream};
+use futures::{future::BoxFuture, AsyncRead, AsyncWrite, FutureExt};
+use loco_protocol::secure::{
+    crypto::CryptoStore, session::SecureClientSession, stream::SecureStream,
+};
+use tokio_native_tls::{native_tls, TlsConnector};
+
+use crate::command::tokio::TokioStream;
+
+use super::transport::{Endpoint, ServerKind, TransportFactory};
+
+pub trait TokioTransportStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> TokioTransportStream for T {}
+
+/// Connects using tokio tcp stream.
+/// Booking server uses native tls, checkin and loco server use loco secure layer.
+/// Streams and futures are [Send] so sessions can run on multi thread runtime.
+#[derive(Clone)]
+pub struct TokioTransport {
+    loco_session: Arc<SecureClientSession>,
+    tls_connector: TlsConnector,
+}
+
+impl TokioTransport {
+    pub fn new(loco_session: SecureClientSession) -> io::Result<Self> {
+        let tls_connector = native_tls::TlsConnector::new().map_err(io::Error::other)?;
+
+        Ok(Self::with_tls(loco_session, tls_connector.into()))
+    }
+
+    /// Create transport using custom [TlsConnector]
+    pub fn with_tls(loco_session: SecureClientSession, tls_connector: TlsConnector) -> Self {
+        Self {
+            loco_session: Arc::new(loco_session),
+            tls_connector,
+        }
+    }
+}
+
+impl TransportFactory for TokioTransport {
+    type Stream = Box<dyn TokioTransportStream>;
+    type Future = BoxFuture<'static, io::Result<Self::Stream>>;
+
+    fn connect(&self, endpoint: &Endpoint) -> Self::Future {
+        let loco_session = self.loco_session.clone();
+        let tls_connector = self.tls_connector.clone();
+        let endpoint = endpoint.clone();
+
+        async move {
+            let stream =
+                BufStream::new(TcpStream::connect((endpoint.host.as_str(), endpoint.port)).await?);
+
+            match endpoint.kind {
+                ServerKind::Booking => {
+                    let stream = tls_connector
+                        .connect(&endpoint.host, stream)
+                        .await
+                        .map_err(io::Error::other)?;
+
+                    Ok(Box::new(TokioStream::new(stream)) as Box<dyn TokioTransportStream>)
+                }
+
+                ServerKind::Checkin | ServerKind::Loco => {
+                    let mut stream =
+                        SecureStream::new(CryptoStore::new(), TokioStream::new(stream));
+
+                    loco_session
+                        .handshake_async(&mut stream)
+                        .await
+                        .map_err(|err| io::Error::other(err.to_string()))?;
+
+                    Ok(Box::new(stream) as Box<dyn TokioTransportStream>)
+                }
+            }
+        }
+        .boxed()
+    }
+}
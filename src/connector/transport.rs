@@ -0,0 +1,56 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{fmt::Display, io};
+
+use futures::{AsyncRead, AsyncWrite, Future};
+
+/// Kind of loco server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerKind {
+    /// Booking server. Uses tls.
+    Booking,
+
+    /// Checkin server. Uses loco secure layer.
+    Checkin,
+
+    /// Loco server. Uses loco secure layer.
+    Loco,
+}
+
+/// Server address to connect
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    pub kind: ServerKind,
+
+    pub host: String,
+    pub port: u16,
+}
+
+impl Endpoint {
+    pub fn new(kind: ServerKind, host: impl Into<String>, port: u16) -> Self {
+        Self {
+            kind,
+            host: host.into(),
+            port,
+        }
+    }
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}({}:{})", self.kind, self.host, self.port)
+    }
+}
+
+/// Opens stream to [Endpoint].
+/// Implementation should finish every handshake (tls, loco secure layer) required by [Endpoint::kind].
+pub trait TransportFactory {
+    type Stream: AsyncRead + AsyncWrite + Unpin;
+    type Future: Future<Output = io::Result<Self::Stream>>;
+
+    fn connect(&self, endpoint: &Endpoint) -> Self::Future;
+}
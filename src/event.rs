@@ -0,0 +1,167 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! Typed server push commands
+
+use std::{error::Error, fmt::Display};
+
+use bson::Document;
+use futures::{stream, AsyncRead, Stream, StreamExt};
+
+use crate::{
+    command::{
+        codec::ReadError, driver::BroadcastReceiver, session::BsonCommandSession, BsonCommand,
+    },
+    response::chat::{
+        ChangeSvr, ChgMeta, DecunRead, Kickout, Left, Msg, NewMem, SyncDlMsg, SyncJoin, SyncLinkCr,
+        SyncLinkPf, SyncMemT, SyncRewr,
+    },
+};
+
+/// Server push command decoded by method
+#[derive(Debug, Clone)]
+pub enum LocoEvent {
+    /// MSG
+    Msg(Msg),
+
+    /// DECUNREAD
+    DecunRead(DecunRead),
+
+    /// CHGMETA
+    ChgMeta(ChgMeta),
+
+    /// KICKOUT
+    Kickout(Kickout),
+
+    /// CHANGESVR
+    ChangeSvr(ChangeSvr),
+
+    /// NEWMEM
+    NewMem(NewMem),
+
+    /// LEFT
+    Left(Left),
+
+    /// SYNCDLMSG
+    SyncDlMsg(SyncDlMsg),
+
+    /// SYNCJOIN
+    SyncJoin(SyncJoin),
+
+    /// SYNCLINKCR
+    SyncLinkCr(SyncLinkCr),
+
+    /// SYNCMEMT
+    SyncMemT(SyncMemT),
+
+    /// SYNCLINKPF
+    SyncLinkPf(SyncLinkPf),
+
+    /// SYNCREWR
+    SyncRewr(SyncRewr),
+
+    /// Command not known by this crate
+    Unknown(BsonCommand<Document>),
+}
+
+impl LocoEvent {
+    /// Decode [BsonCommand] using its method
+    pub fn decode(command: BsonCommand<Document>) -> Result<Self, bson::de::Error> {
+        Ok(match &*command.method {
+            "MSG" => Self::Msg(bson::from_document(command.data)?),
+            "DECUNREAD" => Self::DecunRead(bson::from_document(command.data)?),
+            "CHGMETA" => Self::ChgMeta(bson::from_document(command.data)?),
+            "KICKOUT" => Self::Kickout(bson::from_document(command.data)?),
+            // Has no data
+            "CHANGESVR" => Self::ChangeSvr(ChangeSvr),
+            "NEWMEM" => Self::NewMem(bson::from_document(command.data)?),
+            "LEFT" => Self::Left(bson::from_document(command.data)?),
+            "SYNCDLMSG" => Self::SyncDlMsg(bson::from_document(command.data)?),
+            "SYNCJOIN" => Self::SyncJoin(bson::from_document(command.data)?),
+            "SYNCLINKCR" => Self::SyncLinkCr(bson::from_document(command.data)?),
+            "SYNCMEMT" => Self::SyncMemT(bson::from_document(command.data)?),
+            "SYNCLINKPF" => Self::SyncLinkPf(bson::from_document(command.data)?),
+            "SYNCREWR" => Self::SyncRewr(bson::from_document(command.data)?),
+
+            _ => Self::Unknown(command),
+        })
+    }
+
+    /// Method name of this event
+    pub fn method(&self) -> &str {
+        match self {
+            Self::Msg(_) => "MSG",
+            Self::DecunRead(_) => "DECUNREAD",
+            Self::ChgMeta(_) => "CHGMETA",
+            Self::Kickout(_) => "KICKOUT",
+            Self::ChangeSvr(_) => "CHANGESVR",
+            Self::NewMem(_) => "NEWMEM",
+            Self::Left(_) => "LEFT",
+            Self::SyncDlMsg(_) => "SYNCDLMSG",
+            Self::SyncJoin(_) => "SYNCJOIN",
+            Self::SyncLinkCr(_) => "SYNCLINKCR",
+            Self::SyncMemT(_) => "SYNCMEMT",
+            Self::SyncLinkPf(_) => "SYNCLINKPF",
+            Self::SyncRewr(_) => "SYNCREWR",
+            Self::Unknown(command) => &command.method,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EventError {
+    Read(ReadError),
+    Decode(bson::de::Error),
+}
+
+impl From<ReadError> for EventError {
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl From<bson::de::Error> for EventError {
+    fn from(err: bson::de::Error) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl Display for EventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventError::Read(err) => err.fmt(f),
+            EventError::Decode(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for EventError {}
+
+/// Stream of [LocoEvent] read from [BsonCommandSession].
+/// Ends after first read error.
+pub fn session_events<S: AsyncRead + Unpin>(
+    session: &mut BsonCommandSession<S>,
+) -> impl Stream<Item = Result<LocoEvent, EventError>> + '_ {
+    stream::unfold(Some(session), |session| async move {
+        let session = session?;
+
+        match session.read_async().await {
+            Ok(read) => Some((
+                LocoEvent::decode(read.command).map_err(EventError::from),
+                Some(session),
+            )),
+
+            Err(err) => Some((Err(EventError::from(err)), None)),
+        }
+    })
+}
+
+/// Stream of [LocoEvent] from [BroadcastReceiver] of session driver
+pub fn broadcast_events(
+    receiver: BroadcastReceiver,
+) -> impl Stream<Item = Result<LocoEvent, bson::de::Error>> {
+    receiver.map(|read| LocoEvent::decode(read.command))
+}
@@ -8,6 +8,10 @@
 //! See [request], [response] module for command datas.
 //! See [structs] module for types used in command datas.
 //! See [client] module for client implementation.
+//! See [event] module for typed server push commands.
+//! See [connector] module for connection bootstrap.
+//! See [server] module for server implementation.
+//! Enable `tower` feature for `tower::Service` integration in `service` module.
 
 pub mod request;
 pub mod response;
@@ -17,3 +21,14 @@ pub mod structs;
 pub mod command;
 
 pub mod client;
+
+pub mod event;
+
+pub mod connector;
+
+pub mod server;
+
+#[cfg(feature = "tower")]
+pub mod service;
+
+mod time;
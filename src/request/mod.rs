@@ -11,4 +11,26 @@ pub mod chat;
 
 pub mod ping;
 
-pub use ping::Ping;
\ No newline at end of file
+pub use ping::Ping;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::command::BsonCommand;
+
+/// Request command data paired with its method and response type.
+/// Implement this to send commands not included in clients.
+pub trait LocoCommand: Serialize {
+    /// Command method
+    const METHOD: &'static str;
+
+    /// Command data type
+    const DATA_TYPE: i8 = 0;
+
+    /// Response data type
+    type Response: DeserializeOwned;
+
+    /// Create [BsonCommand] with this request as data
+    fn to_command(&self) -> BsonCommand<&Self> {
+        BsonCommand::new_const(Self::METHOD, Self::DATA_TYPE, self)
+    }
+}
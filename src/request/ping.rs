@@ -6,8 +6,16 @@
 
 use serde::{Serialize, Deserialize};
 
+use super::LocoCommand;
+
 /// Signal server to keep connection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ping {
 
 }
+
+impl LocoCommand for Ping {
+    const METHOD: &'static str = "PING";
+
+    type Response = ();
+}
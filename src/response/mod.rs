@@ -11,6 +11,8 @@ pub mod chat;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{client::LocoError, structs::client::Status};
+
 /// Common Response data with status code
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseData<T> {
@@ -19,3 +21,19 @@ pub struct ResponseData<T> {
     #[serde(flatten)]
     pub data: Option<T>,
 }
+
+impl<T> ResponseData<T> {
+    /// Response status
+    pub fn status(&self) -> Status {
+        Status::from(self.status)
+    }
+
+    /// Returns data if status is [Status::Success].
+    /// Fails with [LocoError::Status] on other status, [LocoError::MissingData] if there are no data.
+    pub fn into_result(self) -> Result<T, LocoError> {
+        match self.status() {
+            Status::Success => self.data.ok_or(LocoError::MissingData),
+            status => Err(LocoError::Status(status)),
+        }
+    }
+}
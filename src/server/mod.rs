@@ -0,0 +1,219 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! Loco server implementation.
+//! Useful for testing clients without official server.
+
+pub mod router;
+
+use std::{error::Error, fmt::Display, io, sync::Arc};
+
+use bson::Document;
+use futures::{
+    channel::mpsc,
+    future::{self, Either},
+    pin_mut, AsyncRead, AsyncReadExt, AsyncWrite, StreamExt,
+};
+use loco_protocol::command::codec::StreamError;
+use serde::Serialize;
+
+use crate::command::{
+    codec::{BsonCommandCodec, ReadError, WriteError},
+    BsonCommand, ReadBsonCommand,
+};
+
+pub use router::Router;
+
+/// Request id of server pushed commands
+pub const PUSH_ID: i32 = -1;
+
+#[derive(Debug)]
+pub enum PushError {
+    Encode(bson::ser::Error),
+
+    /// Connection is closed
+    Closed,
+}
+
+impl From<bson::ser::Error> for PushError {
+    fn from(err: bson::ser::Error) -> Self {
+        Self::Encode(err)
+    }
+}
+
+impl Display for PushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::Encode(err) => err.fmt(f),
+            PushError::Closed => write!(f, "Connection closed"),
+        }
+    }
+}
+
+impl Error for PushError {}
+
+/// Loco server dispatching requests using [Router]
+#[derive(Debug, Clone)]
+pub struct LocoServer {
+    router: Arc<Router>,
+}
+
+impl LocoServer {
+    pub fn new(router: Router) -> Self {
+        Self {
+            router: Arc::new(router),
+        }
+    }
+
+    /// Serve client connected with stream
+    pub fn accept<S>(&self, stream: S) -> (ServerConnection<S>, ServerPusher) {
+        let (sender, receiver) = mpsc::unbounded();
+
+        (
+            ServerConnection {
+                stream,
+                router: self.router.clone(),
+                sender: sender.clone(),
+                receiver,
+            },
+            ServerPusher { sender },
+        )
+    }
+}
+
+/// Cloneable handle for pushing commands to connected client
+#[derive(Debug, Clone)]
+pub struct ServerPusher {
+    sender: mpsc::UnboundedSender<ReadBsonCommand<Document>>,
+}
+
+impl ServerPusher {
+    /// Push command to client
+    pub fn push(&self, command: &BsonCommand<impl Serialize>) -> Result<(), PushError> {
+        let data = bson::to_document(&command.data)?;
+
+        self.sender
+            .unbounded_send(ReadBsonCommand {
+                id: PUSH_ID,
+                command: BsonCommand {
+                    method: command.method.clone(),
+                    data_type: command.data_type,
+                    data,
+                },
+            })
+            .map_err(|_| PushError::Closed)
+    }
+
+    /// Returns true if connection is closed
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// Connection of one client.
+/// The connection does nothing until [ServerConnection::run] future is polled.
+#[derive(Debug)]
+pub struct ServerConnection<S> {
+    stream: S,
+    router: Arc<Router>,
+
+    sender: mpsc::UnboundedSender<ReadBsonCommand<Document>>,
+    receiver: mpsc::UnboundedReceiver<ReadBsonCommand<Document>>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ServerConnection<S> {
+    /// Run connection until the stream fails.
+    /// Returns Ok if client closes connection.
+    pub async fn run(self) -> Result<(), ServerError> {
+        let Self {
+            stream,
+            router,
+            sender,
+            mut receiver,
+        } = self;
+
+        let (read_half, write_half) = stream.split();
+
+        let write_task = async {
+            let mut codec = BsonCommandCodec::new(write_half);
+
+            while let Some(ReadBsonCommand { id, command }) = receiver.next().await {
+                codec.write_async(id, &command).await?;
+                codec
+                    .flush_async()
+                    .await
+                    .map_err(|err| WriteError::Codec(StreamError::Io(err)))?;
+            }
+
+            Ok::<_, WriteError>(())
+        };
+
+        let read_task = async {
+            let mut codec = BsonCommandCodec::new(read_half);
+
+            loop {
+                let ReadBsonCommand { id, command } = match codec.read_async().await {
+                    Ok(read) => read,
+
+                    // Client closed connection on frame boundary
+                    Err(ReadError::Stream(StreamError::Io(err)))
+                        if err.kind() == io::ErrorKind::UnexpectedEof
+                            && !codec.has_partial_frame() =>
+                    {
+                        break Ok(());
+                    }
+
+                    Err(err) => break Err(err),
+                };
+
+                let response = router.handle(command);
+
+                sender
+                    .unbounded_send(ReadBsonCommand {
+                        id,
+                        command: response,
+                    })
+                    .ok();
+            }
+        };
+
+        pin_mut!(write_task, read_task);
+
+        match future::select(read_task, write_task).await {
+            Either::Left((res, _)) => Ok(res?),
+            Either::Right((res, _)) => Ok(res?),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ServerError {
+    Write(WriteError),
+    Read(ReadError),
+}
+
+impl From<WriteError> for ServerError {
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
+impl From<ReadError> for ServerError {
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerError::Write(err) => err.fmt(f),
+            ServerError::Read(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ServerError {}
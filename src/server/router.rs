@@ -0,0 +1,73 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+use std::{collections::HashMap, fmt::Debug};
+
+use bson::Document;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{command::BsonCommand, response::ResponseData, structs::client::Status};
+
+type Handler = Box<dyn Fn(Document) -> Document + Send + Sync>;
+
+/// Request handlers keyed by method.
+/// Unknown method and undecodable request are answered with [Status::Fail].
+#[derive(Default)]
+pub struct Router {
+    routes: HashMap<String, Handler>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add handler for method
+    pub fn route<Req, Res, H>(mut self, method: impl Into<String>, handler: H) -> Self
+    where
+        Req: DeserializeOwned,
+        Res: Serialize,
+        H: Fn(Req) -> ResponseData<Res> + Send + Sync + 'static,
+    {
+        self.routes.insert(
+            method.into(),
+            Box::new(move |data| match bson::from_document::<Req>(data) {
+                Ok(request) => bson::to_document(&handler(request))
+                    .unwrap_or_else(|_| status_document(Status::Fail.code())),
+
+                Err(_) => status_document(Status::Fail.code()),
+            }),
+        );
+
+        self
+    }
+
+    /// Handle request and returns response command
+    pub fn handle(&self, command: BsonCommand<Document>) -> BsonCommand<Document> {
+        let data = match self.routes.get(&*command.method) {
+            Some(handler) => handler(command.data),
+            None => status_document(Status::Fail.code()),
+        };
+
+        BsonCommand {
+            method: command.method,
+            data_type: command.data_type,
+            data,
+        }
+    }
+}
+
+impl Debug for Router {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn status_document(status: i16) -> Document {
+    bson::doc! { "status": status as i32 }
+}
@@ -0,0 +1,137 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+//! [Service] implementation of request path.
+//! Wrap [SessionHandle] with tower layers, [TypedService] for typed request and response.
+
+use std::{
+    error::Error,
+    marker::PhantomData,
+    task::{Context, Poll},
+};
+
+use bson::Document;
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt,
+};
+use tower_service::Service;
+
+use crate::{
+    client::RequestError,
+    command::{
+        codec::{deserialize_command, WriteError},
+        driver::SessionHandle,
+        BsonCommand,
+    },
+    request::LocoCommand,
+    response::ResponseData,
+};
+
+impl Service<BsonCommand<Document>> for SessionHandle {
+    type Response = BsonCommand<Document>;
+    type Error = RequestError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.is_closed() {
+            Poll::Ready(Err(RequestError::closed()))
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, command: BsonCommand<Document>) -> Self::Future {
+        let handle = self.clone();
+
+        async move { handle.request(&command).await }.boxed()
+    }
+}
+
+/// Boxed error of [TypedService], same as error of tower layers
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// [Service] adapter taking [LocoCommand] and returning its [ResponseData].
+/// Inner service can be wrapped with tower layers failing with [BoxError].
+/// Errors of request are [RequestError] with method attached.
+#[derive(Debug)]
+pub struct TypedService<Svc, C> {
+    inner: Svc,
+    _phantom: PhantomData<fn(C)>,
+}
+
+impl<Svc, C> TypedService<Svc, C> {
+    pub fn new(inner: Svc) -> Self {
+        Self {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &Svc {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut Svc {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Svc {
+        self.inner
+    }
+}
+
+impl<Svc: Clone, C> Clone for TypedService<Svc, C> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<Svc, C> Service<C> for TypedService<Svc, C>
+where
+    Svc: Service<BsonCommand<Document>, Response = BsonCommand<Document>>,
+    Svc::Error: Into<BoxError>,
+    Svc::Future: Send + 'static,
+    C: LocoCommand,
+    C::Response: Send + 'static,
+{
+    type Response = ResponseData<C::Response>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, command: C) -> Self::Future {
+        let data = match bson::to_document(&command) {
+            Ok(data) => data,
+            Err(err) => {
+                let err = RequestError::from(WriteError::Encode(err)).with_method(C::METHOD);
+
+                return future::ready(Err(BoxError::from(err))).boxed();
+            }
+        };
+
+        let res = self
+            .inner
+            .call(BsonCommand::new_const(C::METHOD, C::DATA_TYPE, data));
+
+        async move {
+            let response = res.await.map_err(|err| {
+                match Into::<BoxError>::into(err).downcast::<RequestError>() {
+                    Ok(err) => (*err).with_method(C::METHOD).into(),
+                    Err(err) => err,
+                }
+            })?;
+
+            Ok(deserialize_command::<ResponseData<C::Response>>(response)
+                .map_err(|err| RequestError::from(err).with_method(C::METHOD))?
+                .data)
+        }
+        .boxed()
+    }
+}
@@ -6,17 +6,84 @@
 
 use serde::{Deserialize, Serialize};
 
-/// Describes response status info
-///
-/// Compare these predefined status before to process data
-#[derive(Debug, PartialEq)]
-#[repr(i16)]
-pub enum Status {
+macro_rules! status_codes {
+    (
+        $(
+            $(#[$meta:meta])*
+            $name: ident = $code: literal,
+        )*
+    ) => {
+        /// Describes response status info
+        ///
+        /// Compare these predefined status before to process data
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Status {
+            $(
+                $(#[$meta])*
+                $name,
+            )*
+
+            /// Status not known by this crate
+            Unknown(i16),
+        }
+
+        impl Status {
+            /// Raw status code
+            pub const fn code(self) -> i16 {
+                match self {
+                    $(Self::$name => $code,)*
+                    Self::Unknown(code) => code,
+                }
+            }
+        }
+
+        impl From<i16> for Status {
+            fn from(code: i16) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    _ => Self::Unknown(code),
+                }
+            }
+        }
+    };
+}
+
+status_codes! {
     Success = 0,
+    InvalidUser = -1,
+    ClientError = -200,
+    NotLogon = -201,
+    InvalidMethod = -202,
+    InvalidParameter = -203,
+    InvalidHeader = -204,
+    ChatSpamLimit = -303,
+    RestrictedApp = -304,
+    InvalidChannel = -401,
+    ChatBlockedByFriend = -402,
+    NotChatableUser = -403,
+    BlockedIp = -444,
+    BackgroundLoginBlocked = -445,
+    /// Operation denied
     Fail = -500,
+    ChannelUserLimited = -501,
+    InvalidAccessToken = -950,
+    /// Blocked account
     Restricted = -997,
+    AuthRequired = -998,
+    UpdateRequired = -999,
     Maintenance = -9797,
-    NotLogon = -201,
+}
+
+impl Status {
+    pub const fn is_success(self) -> bool {
+        matches!(self, Self::Success)
+    }
+}
+
+impl From<Status> for i16 {
+    fn from(status: Status) -> Self {
+        status.code()
+    }
 }
 
 /// Common client info struct
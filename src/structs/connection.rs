@@ -4,6 +4,8 @@
  * Copyright (c) storycraft. Licensed under the MIT Licence.
  */
 
+use std::time::Duration;
+
 use serde::{Serialize, Deserialize};
 
 /// ConnectionData includes ports, connection configuartion
@@ -59,6 +61,28 @@ pub struct ConnectionData {
 
 }
 
+impl ConnectionData {
+    /// Request timeout as [Duration]
+    pub fn request_timeout_duration(&self) -> Duration {
+        Duration::from_secs(self.request_timeout.max(0) as u64)
+    }
+
+    /// Connection timeout as [Duration]
+    pub fn connection_timeout_duration(&self) -> Duration {
+        Duration::from_secs(self.connection_timeout.max(0) as u64)
+    }
+
+    /// Ping interval as [Duration]
+    pub fn ping_interval_duration(&self) -> Duration {
+        Duration::from_secs(self.ping_interval.max(0) as u64)
+    }
+
+    /// Background ping interval as [Duration]
+    pub fn background_interval_duration(&self) -> Duration {
+        Duration::from_secs(self.background_interval.max(0) as u64)
+    }
+}
+
 /// HostData includes host list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HostData {
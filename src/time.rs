@@ -0,0 +1,22 @@
+/*
+ * Created on Sun Oct 18 2026
+ *
+ * Copyright (c) storycraft. Licensed under the MIT Licence.
+ */
+
+/// Current unix millis
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+pub(crate) fn now_millis() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Current unix millis. `SystemTime` is not available on wasm.
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub(crate) fn now_millis() -> i64 {
+    js_sys::Date::now() as i64
+}